@@ -0,0 +1,68 @@
+//! Runs the game from a bare crossterm event loop, the way an app that keeps
+//! its own main loop would embed it in one of its panes.
+
+use std::io::{self, Stdout, Write};
+use std::time::{Duration, Instant};
+
+use console_engine::crossterm::{cursor, event, execute, queue, style, terminal};
+use console_engine::events::Event;
+use console_engine::pixel::Pixel;
+use console_snek::{Config, Renderer, SnekApp};
+
+// Where the game's pane sits in the terminal.
+const PANE_X: u16 = 2;
+const PANE_Y: u16 = 1;
+
+struct Pane {
+    out: Stdout,
+}
+
+impl Renderer for Pane {
+    fn draw(&mut self, x: u16, y: u16, pixel: Pixel) {
+        // A cell that fails to draw is redrawn on the next frame anyway.
+        let _ = queue!(
+            self.out,
+            cursor::MoveTo(PANE_X + x, PANE_Y + y),
+            style::SetForegroundColor(pixel.fg),
+            style::SetBackgroundColor(pixel.bg),
+            style::Print(pixel.chr)
+        );
+    }
+}
+
+fn main() -> io::Result<()> {
+    let mut app = match SnekApp::new(Config::default()) {
+        Ok(app) => app,
+        Err(error) => {
+            eprintln!("The default settings can't be played: {}", error);
+            return Ok(());
+        }
+    };
+    let mut pane = Pane { out: io::stdout() };
+    terminal::enable_raw_mode()?;
+    execute!(pane.out, terminal::EnterAlternateScreen, cursor::Hide)?;
+
+    let mut last_tick = Instant::now();
+    while !app.is_finished() {
+        // Wait for input, but never so long that a frame is missed.
+        if event::poll(Duration::from_millis(5))? {
+            if let event::Event::Key(key) = event::read()? {
+                app.handle_event(Event::Key(key));
+            }
+        }
+        let now = Instant::now();
+        if app.tick(now - last_tick) {
+            app.render_into(&mut pane);
+            pane.out.flush()?;
+        }
+        last_tick = now;
+    }
+
+    execute!(
+        pane.out,
+        style::ResetColor,
+        cursor::Show,
+        terminal::LeaveAlternateScreen
+    )?;
+    terminal::disable_raw_mode()
+}
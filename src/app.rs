@@ -0,0 +1,155 @@
+use std::mem;
+use std::time::Duration;
+
+use console_engine::events::Event;
+use console_engine::pixel::Pixel;
+use console_engine::{KeyCode, KeyModifiers};
+use wolf_engine::{Context, State, Transition};
+
+use crate::config::{Config, ConfigError};
+use crate::exit::ExitStatus;
+use crate::high_scores::HighScores;
+use crate::{get_console, ConsoleContext, RunConfig, TitleState, TICK_RATE};
+
+/// The most play a single tick catches up on.  A host that stalls for a
+/// while carries on from where it was instead of racing through the frames
+/// it missed.
+const MAX_CATCH_UP: Duration = Duration::from_millis(250);
+
+/// Somewhere an embedded game's frames go, like a pane in the host's UI.
+pub trait Renderer {
+    /// Draws `pixel` at `x`, `y`, counted from the game's top left corner.
+    fn draw(&mut self, x: u16, y: u16, pixel: Pixel);
+}
+
+/// The game for another app's event loop to drive, one frame at a time,
+/// instead of taking over the terminal.  Hand it the terminal's events with
+/// [`handle_event`](Self::handle_event), let it know as time passes with
+/// [`tick`](Self::tick), and copy out what it drew with
+/// [`render_into`](Self::render_into).
+pub struct SnekApp {
+    context: Context,
+    size: (u16, u16),
+    /// The screens being played, the one on top getting the frames.
+    states: Vec<Box<dyn State>>,
+    /// The keys pressed since the last frame, in the order they arrived.
+    keys: Vec<(KeyCode, KeyModifiers)>,
+    /// Time that has passed without adding up to a whole frame yet.
+    unplayed: Duration,
+}
+
+impl SnekApp {
+    /// Starts on the title screen with `config`'s settings.  High scores
+    /// are kept for as long as the app runs but never saved.
+    pub fn new(config: Config) -> Result<Self, ConfigError> {
+        let board = config.board().map_err(ConfigError::Board)?;
+        let size = board.size();
+        let mut context = Context::new();
+        context
+            .add(ConsoleContext::headless(size.0, size.1))
+            .expect("failed to add ConsoleContext");
+        context
+            .add(ExitStatus::default())
+            .expect("failed to add ExitStatus");
+        context
+            .add(HighScores::load(None))
+            .expect("failed to add HighScores");
+        let run_config = RunConfig::from_settings(&config, board);
+        context.add(config).expect("failed to add Config");
+
+        let mut app = Self {
+            context,
+            size,
+            states: Vec::new(),
+            keys: Vec::new(),
+            unplayed: Duration::ZERO,
+        };
+        app.push(Box::from(TitleState::new(run_config)));
+        Ok(app)
+    }
+
+    /// The size of the picture the game draws, in cells.
+    pub fn size(&self) -> (u16, u16) {
+        self.size
+    }
+
+    /// Whether the player has quit, after which ticks do nothing.
+    pub fn is_finished(&self) -> bool {
+        self.states.is_empty()
+    }
+
+    /// Takes in an event from the host's terminal.  Key presses count on the
+    /// next frame; everything else is ignored.
+    pub fn handle_event(&mut self, event: Event) {
+        if let Event::Key(key) = event {
+            self.keys.push((key.code, key.modifiers));
+        }
+    }
+
+    /// Plays however many frames `elapsed` adds up to at the game's frame
+    /// rate, returning whether any were played and there's a new picture.
+    pub fn tick(&mut self, elapsed: Duration) -> bool {
+        let frame = Duration::from_secs(1) / TICK_RATE;
+        self.unplayed = (self.unplayed + elapsed).min(MAX_CATCH_UP);
+        let mut played = false;
+        while self.unplayed >= frame && !self.is_finished() {
+            self.unplayed -= frame;
+            self.play_frame();
+            played = true;
+        }
+        played
+    }
+
+    /// Copies the picture of the last frame played into `renderer`.
+    pub fn render_into(&self, renderer: &mut dyn Renderer) {
+        self.context
+            .get::<ConsoleContext>()
+            .expect("no ConsoleContext")
+            .render_into(renderer);
+    }
+
+    // Runs the screen on top for a frame, the way the engine's loop does
+    // for the binary.
+    fn play_frame(&mut self) {
+        let keys = mem::take(&mut self.keys);
+        get_console(&mut self.context).start_frame(keys);
+        let Some(state) = self.states.last_mut() else {
+            return;
+        };
+        if let Some(transition) = state.update(&mut self.context) {
+            self.apply(transition);
+        }
+        if let Some(state) = self.states.last_mut() {
+            state.render(&mut self.context);
+        }
+    }
+
+    fn apply(&mut self, transition: Transition) {
+        match transition {
+            Transition::Push(state) => self.push(state),
+            Transition::Pop => self.pop(),
+            Transition::CleanPush(state) => {
+                self.clean();
+                self.push(state);
+            }
+            Transition::Clean | Transition::Quit => self.clean(),
+        }
+    }
+
+    fn push(&mut self, mut state: Box<dyn State>) {
+        state.setup(&mut self.context);
+        self.states.push(state);
+    }
+
+    fn pop(&mut self) {
+        if let Some(mut state) = self.states.pop() {
+            state.shutdown(&mut self.context);
+        }
+    }
+
+    fn clean(&mut self) {
+        while !self.states.is_empty() {
+            self.pop();
+        }
+    }
+}
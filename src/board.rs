@@ -0,0 +1,129 @@
+use std::fmt;
+use std::num::NonZeroU16;
+
+use crate::Vector2;
+
+pub const MAX_BOARD_DIMENSION: u16 = 1024;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BoardError {
+    ZeroWidth,
+    ZeroHeight,
+    TooLarge { width: u16, height: u16 },
+}
+
+impl fmt::Display for BoardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ZeroWidth => write!(f, "the board width must be at least 1"),
+            Self::ZeroHeight => write!(f, "the board height must be at least 1"),
+            Self::TooLarge { width, height } => write!(
+                f,
+                "a {}x{} board is too large, boards can be at most {}x{}",
+                width, height, MAX_BOARD_DIMENSION, MAX_BOARD_DIMENSION
+            ),
+        }
+    }
+}
+
+// Board dimensions are validated once here and handed out as i32 for game
+// math, which every u16 fits into losslessly.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Board {
+    width: NonZeroU16,
+    height: NonZeroU16,
+}
+
+impl Default for Board {
+    fn default() -> Self {
+        Self::new(80, 20).expect("the default board dimensions are valid")
+    }
+}
+
+impl Board {
+    pub fn new(width: u16, height: u16) -> Result<Self, BoardError> {
+        if width > MAX_BOARD_DIMENSION || height > MAX_BOARD_DIMENSION {
+            return Err(BoardError::TooLarge { width, height });
+        }
+        Ok(Self {
+            width: NonZeroU16::new(width).ok_or(BoardError::ZeroWidth)?,
+            height: NonZeroU16::new(height).ok_or(BoardError::ZeroHeight)?,
+        })
+    }
+
+    pub fn size(&self) -> (u16, u16) {
+        (self.width.get(), self.height.get())
+    }
+
+    pub fn width(&self) -> i32 {
+        self.width.get().into()
+    }
+
+    pub fn height(&self) -> i32 {
+        self.height.get().into()
+    }
+
+    pub fn wrap(&self, location: Vector2) -> Vector2 {
+        Vector2::new(
+            location.x.rem_euclid(self.width()),
+            location.y.rem_euclid(self.height()),
+        )
+    }
+
+    pub fn neighbors(&self, location: Vector2) -> [Vector2; 4] {
+        [(0, -1), (0, 1), (-1, 0), (1, 0)]
+            .map(|(x, y)| self.wrap(Vector2::new(location.x + x, location.y + y)))
+    }
+
+    pub fn edge_distance(&self, location: Vector2) -> i32 {
+        location
+            .x
+            .min(location.y)
+            .min(self.width() - 1 - location.x)
+            .min(self.height() - 1 - location.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zero_dimensions_are_rejected() {
+        assert_eq!(Board::new(0, 20), Err(BoardError::ZeroWidth));
+        assert_eq!(Board::new(80, 0), Err(BoardError::ZeroHeight));
+        assert_eq!(
+            BoardError::ZeroWidth.to_string(),
+            "the board width must be at least 1"
+        );
+        assert_eq!(
+            BoardError::ZeroHeight.to_string(),
+            "the board height must be at least 1"
+        );
+    }
+
+    #[test]
+    fn oversized_boards_are_rejected() {
+        assert!(Board::new(MAX_BOARD_DIMENSION, MAX_BOARD_DIMENSION).is_ok());
+        let error = Board::new(MAX_BOARD_DIMENSION + 1, 1).unwrap_err();
+        assert_eq!(
+            error,
+            BoardError::TooLarge {
+                width: MAX_BOARD_DIMENSION + 1,
+                height: 1
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "a 1025x1 board is too large, boards can be at most 1024x1024"
+        );
+    }
+
+    #[test]
+    fn oversized_boards_are_reported_before_zero_dimensions() {
+        assert!(matches!(
+            Board::new(0, MAX_BOARD_DIMENSION + 1),
+            Err(BoardError::TooLarge { .. })
+        ));
+    }
+}
@@ -0,0 +1,118 @@
+use std::path::PathBuf;
+
+use crate::exit::ExitCodeMode;
+use crate::startup::StartupError;
+
+#[derive(Default)]
+pub struct Options {
+    pub exit_code_mode: ExitCodeMode,
+    pub self_test: bool,
+    /// Where to save a replay of each finished run.
+    pub record: Option<PathBuf>,
+    /// A replay to watch instead of playing.
+    pub replay: Option<PathBuf>,
+}
+
+const EXIT_CODE_MODES: &str = "--exit-code-mode normal or --exit-code-mode outcome";
+
+impl Options {
+    pub fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, StartupError> {
+        let mut options = Self::default();
+        while let Some(arg) = args.next() {
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) => (flag.to_string(), Some(value.to_string())),
+                None => (arg.clone(), None),
+            };
+            let mut value = |flag: &'static str, expected: &'static str| {
+                inline_value
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or(StartupError::MissingValue { flag, expected })
+            };
+            match flag.as_str() {
+                "--exit-code-mode" => {
+                    let value = value("--exit-code-mode", EXIT_CODE_MODES)?;
+                    options.exit_code_mode =
+                        ExitCodeMode::parse(&value).ok_or(StartupError::InvalidValue {
+                            flag: "--exit-code-mode",
+                            value,
+                            expected: EXIT_CODE_MODES,
+                        })?;
+                }
+                "--self-test" => options.self_test = true,
+                "--record" => options.record = Some(value("--record", "--record <file>")?.into()),
+                "--replay" => options.replay = Some(value("--replay", "--replay <file>")?.into()),
+                _ => return Err(StartupError::UnknownArgument(arg)),
+            }
+        }
+        if options.record.is_some() && options.replay.is_some() {
+            return Err(StartupError::ConflictingArguments {
+                first: "--record",
+                second: "--replay",
+            });
+        }
+        Ok(options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Options, StartupError> {
+        Options::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    fn error(args: &[&str]) -> String {
+        match parse(args) {
+            Ok(_) => panic!("{:?} parsed", args),
+            Err(error) => error.to_string(),
+        }
+    }
+
+    #[test]
+    fn flags_parse() {
+        let options = parse(&[
+            "--self-test",
+            "--exit-code-mode=outcome",
+            "--record",
+            "run.replay",
+        ])
+        .expect("the flags are valid");
+        assert!(options.self_test);
+        assert_eq!(options.exit_code_mode, ExitCodeMode::Outcome);
+        assert_eq!(options.record, Some(PathBuf::from("run.replay")));
+        assert_eq!(options.replay, None);
+    }
+
+    #[test]
+    fn unknown_arguments_are_named() {
+        assert_eq!(error(&["--fast"]), "unrecognized argument \"--fast\"");
+    }
+
+    #[test]
+    fn values_must_be_given() {
+        assert_eq!(error(&["--record"]), "--record needs a value");
+        assert_eq!(error(&["--replay"]), "--replay needs a value");
+        assert_eq!(
+            error(&["--exit-code-mode"]),
+            "--exit-code-mode needs a value"
+        );
+    }
+
+    #[test]
+    fn exit_code_modes_are_checked() {
+        assert_eq!(
+            error(&["--exit-code-mode", "loud"]),
+            "\"loud\" is not a valid value for --exit-code-mode"
+        );
+    }
+
+    #[test]
+    fn recording_and_replaying_conflict() {
+        assert_eq!(
+            error(&["--record", "a.replay", "--replay=b.replay"]),
+            "--record can't be used with --replay"
+        );
+    }
+}
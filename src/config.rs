@@ -0,0 +1,920 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use console_engine::pixel::{self, Pixel};
+use console_engine::{Color, KeyCode};
+use wolf_engine::Subcontext;
+
+use crate::board::{self, Board, BoardError};
+use crate::game_mode::{Difficulty, GameMode};
+use crate::{paths, ConsoleContext};
+
+const FILE_NAME: &str = "config.toml";
+const DEFAULT_SPECIAL_FOOD_CHANCE: u32 = 10;
+
+/// Keys the game already uses for something else, which can't steer too.
+pub const RESERVED_KEYS: [char; 11] = ['q', 'o', 'g', 'r', 'p', 's', 'l', 'm', 'y', 'n', 'c'];
+/// The smallest board the settings screen will save.  Smaller boards leave
+/// the snake almost no room to turn.
+pub const MIN_PLAYABLE_WIDTH: u16 = 20;
+pub const MIN_PLAYABLE_HEIGHT: u16 = 10;
+
+pub const COLORS: [(&str, Color); 17] = [
+    ("reset", Color::Reset),
+    ("black", Color::Black),
+    ("dark_grey", Color::DarkGrey),
+    ("grey", Color::Grey),
+    ("white", Color::White),
+    ("red", Color::Red),
+    ("dark_red", Color::DarkRed),
+    ("green", Color::Green),
+    ("dark_green", Color::DarkGreen),
+    ("yellow", Color::Yellow),
+    ("dark_yellow", Color::DarkYellow),
+    ("blue", Color::Blue),
+    ("dark_blue", Color::DarkBlue),
+    ("magenta", Color::Magenta),
+    ("dark_magenta", Color::DarkMagenta),
+    ("cyan", Color::Cyan),
+    ("dark_cyan", Color::DarkCyan),
+];
+
+pub fn color_name(color: Color) -> &'static str {
+    COLORS
+        .iter()
+        .find(|(_, named)| *named == color)
+        .map_or("reset", |(name, _)| name)
+}
+
+fn parse_color(name: &str) -> Option<Color> {
+    COLORS
+        .iter()
+        .find(|(named, _)| *named == name)
+        .map(|(_, color)| *color)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Glyph {
+    pub character: char,
+    pub color: Color,
+}
+
+impl Glyph {
+    pub const fn new(character: char, color: Color) -> Self {
+        Self { character, color }
+    }
+
+    pub fn pixel(self) -> Pixel {
+        pixel::pxl_fg(self.character, self.color)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Style {
+    pub background: Glyph,
+    pub wall: Glyph,
+    pub snake_head: Glyph,
+    pub snake_body: Glyph,
+    pub snake_tail: Glyph,
+    /// Body cells hidden by outline-only rendering.
+    pub snake_interior: Glyph,
+    pub food: Glyph,
+    /// Food that is still spawning in and can't be eaten yet.
+    pub food_spawning: Glyph,
+    pub food_speed_up: Glyph,
+    pub food_slow_down: Glyph,
+    pub food_double_points: Glyph,
+    pub food_shrink: Glyph,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self {
+            background: Glyph::new('.', Color::DarkGrey),
+            wall: Glyph::new('#', Color::Grey),
+            snake_head: Glyph::new('@', Color::DarkGreen),
+            snake_body: Glyph::new('#', Color::Green),
+            snake_tail: Glyph::new('+', Color::DarkGreen),
+            snake_interior: Glyph::new(':', Color::DarkGreen),
+            food: Glyph::new('*', Color::Red),
+            food_spawning: Glyph::new('*', Color::DarkRed),
+            food_speed_up: Glyph::new('>', Color::Yellow),
+            food_slow_down: Glyph::new('<', Color::Blue),
+            food_double_points: Glyph::new('$', Color::Magenta),
+            food_shrink: Glyph::new('-', Color::Cyan),
+        }
+    }
+}
+
+impl Style {
+    fn glyphs(&self) -> [(&'static str, Glyph); 12] {
+        [
+            ("background", self.background),
+            ("wall", self.wall),
+            ("snake_head", self.snake_head),
+            ("snake_body", self.snake_body),
+            ("snake_tail", self.snake_tail),
+            ("snake_interior", self.snake_interior),
+            ("food", self.food),
+            ("food_spawning", self.food_spawning),
+            ("food_speed_up", self.food_speed_up),
+            ("food_slow_down", self.food_slow_down),
+            ("food_double_points", self.food_double_points),
+            ("food_shrink", self.food_shrink),
+        ]
+    }
+
+    fn glyph_mut(&mut self, name: &str) -> Option<&mut Glyph> {
+        match name {
+            "background" => Some(&mut self.background),
+            "wall" => Some(&mut self.wall),
+            "snake_head" => Some(&mut self.snake_head),
+            "snake_body" => Some(&mut self.snake_body),
+            "snake_tail" => Some(&mut self.snake_tail),
+            "snake_interior" => Some(&mut self.snake_interior),
+            "food" => Some(&mut self.food),
+            "food_spawning" => Some(&mut self.food_spawning),
+            "food_speed_up" => Some(&mut self.food_speed_up),
+            "food_slow_down" => Some(&mut self.food_slow_down),
+            "food_double_points" => Some(&mut self.food_double_points),
+            "food_shrink" => Some(&mut self.food_shrink),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub fn config_name(self) -> &'static str {
+        match self {
+            Self::Up => "up",
+            Self::Down => "down",
+            Self::Left => "left",
+            Self::Right => "right",
+        }
+    }
+}
+
+/// A steering key that would either shadow one of the game's own keys or
+/// steer two directions at once.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum KeyClash {
+    Reserved {
+        setting: &'static str,
+        key: KeyCode,
+    },
+    Shared {
+        /// The `[keys]` settings that bind the key.
+        settings: [&'static str; 2],
+        directions: [Direction; 2],
+        key: KeyCode,
+    },
+}
+
+impl KeyClash {
+    /// The `[keys]` settings involved in the clash.
+    pub fn settings(&self) -> &[&'static str] {
+        match self {
+            Self::Reserved { setting, .. } => std::slice::from_ref(setting),
+            Self::Shared { settings, .. } => settings,
+        }
+    }
+}
+
+impl fmt::Display for KeyClash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Reserved { setting, key } => write!(
+                f,
+                "\"{}\" is already used by the game, steer {} with another key",
+                key_name(*key),
+                setting
+            ),
+            Self::Shared {
+                directions, key, ..
+            } => write!(
+                f,
+                "\"{}\" can't steer both {} and {}",
+                key_name(*key),
+                directions[0].config_name(),
+                directions[1].config_name()
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct KeyBindings {
+    pub up: KeyCode,
+    pub down: KeyCode,
+    pub left: KeyCode,
+    pub right: KeyCode,
+    /// Also steer with h, j, k, and l.
+    pub vim_keys: bool,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            up: KeyCode::Up,
+            down: KeyCode::Down,
+            left: KeyCode::Left,
+            right: KeyCode::Right,
+            vim_keys: false,
+        }
+    }
+}
+
+impl KeyBindings {
+    fn letters(up: char, left: char, down: char, right: char) -> Self {
+        Self {
+            up: KeyCode::Char(up),
+            down: KeyCode::Char(down),
+            left: KeyCode::Char(left),
+            right: KeyCode::Char(right),
+            vim_keys: false,
+        }
+    }
+
+    pub fn wasd() -> Self {
+        Self::letters('w', 'a', 's', 'd')
+    }
+
+    pub fn ijkl() -> Self {
+        Self::letters('i', 'j', 'k', 'l')
+    }
+
+    pub fn numpad() -> Self {
+        Self::letters('8', '4', '5', '6')
+    }
+
+    /// The second player's keys in versus mode: WASD, or the first of IJKL
+    /// and the number pad that shares no keys with `first`.  The first
+    /// player would have to bind keys from all three to leave none, and then
+    /// WASD is used anyway.
+    pub fn second_player(first: &Self) -> Self {
+        let taken: Vec<KeyCode> = first.bindings().iter().map(|(_, _, key)| *key).collect();
+        [Self::wasd(), Self::ijkl(), Self::numpad()]
+            .into_iter()
+            .find(|layout| {
+                layout
+                    .bindings()
+                    .iter()
+                    .all(|(_, _, key)| !taken.contains(key))
+            })
+            .unwrap_or_else(Self::wasd)
+    }
+
+    /// A short name for the layout, like "arrows" or "WASD".
+    pub fn label(&self) -> String {
+        let keys = [self.up, self.left, self.down, self.right];
+        if keys == [KeyCode::Up, KeyCode::Left, KeyCode::Down, KeyCode::Right] {
+            return "arrows".to_string();
+        }
+        let separator = if keys.iter().all(|key| matches!(key, KeyCode::Char(_))) {
+            ""
+        } else {
+            "/"
+        };
+        keys.map(|key| key_name(key).to_uppercase()).join(separator)
+    }
+
+    // Every key that steers, with the setting that binds it.
+    fn bindings(&self) -> Vec<(&'static str, Direction, KeyCode)> {
+        let mut bindings = vec![
+            ("up", Direction::Up, self.up),
+            ("down", Direction::Down, self.down),
+            ("left", Direction::Left, self.left),
+            ("right", Direction::Right, self.right),
+        ];
+        if self.vim_keys {
+            for (direction, key) in [
+                (Direction::Up, 'k'),
+                (Direction::Down, 'j'),
+                (Direction::Left, 'h'),
+                (Direction::Right, 'l'),
+            ] {
+                bindings.push(("vim_keys", direction, KeyCode::Char(key)));
+            }
+        }
+        bindings
+    }
+
+    /// Checks that no steering key is one of the game's own keys and that
+    /// every direction has a key to itself.  The vim keys are the game's own
+    /// steering keys, so only clashes with other directions count for them.
+    pub fn check(&self) -> Result<(), KeyClash> {
+        let bindings = self.bindings();
+        for (setting, _, key) in &bindings {
+            if *setting != "vim_keys"
+                && matches!(key, KeyCode::Char(character) if RESERVED_KEYS.contains(character))
+            {
+                return Err(KeyClash::Reserved { setting, key: *key });
+            }
+        }
+        for (index, (setting, direction, key)) in bindings.iter().enumerate() {
+            let shared = bindings[index + 1..]
+                .iter()
+                .find(|(_, other_direction, other_key)| {
+                    other_key == key && other_direction != direction
+                });
+            if let Some((other_setting, other_direction, _)) = shared {
+                return Err(KeyClash::Shared {
+                    settings: [setting, other_setting],
+                    directions: [*direction, *other_direction],
+                    key: *key,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_pressed(&self, console: &ConsoleContext, direction: Direction) -> bool {
+        let (key, vim_key) = match direction {
+            Direction::Up => (self.up, 'k'),
+            Direction::Down => (self.down, 'j'),
+            Direction::Left => (self.left, 'h'),
+            Direction::Right => (self.right, 'l'),
+        };
+        console.is_key_pressed(key)
+            || (self.vim_keys && console.is_key_pressed(KeyCode::Char(vim_key)))
+    }
+}
+
+fn key_name(key: KeyCode) -> String {
+    match key {
+        KeyCode::Up => "Up".to_string(),
+        KeyCode::Down => "Down".to_string(),
+        KeyCode::Left => "Left".to_string(),
+        KeyCode::Right => "Right".to_string(),
+        KeyCode::Char(character) => character.to_string(),
+        other => format!("{:?}", other),
+    }
+}
+
+fn parse_key(name: &str) -> Option<KeyCode> {
+    let mut characters = name.chars();
+    if let (Some(character), None) = (characters.next(), characters.next()) {
+        return Some(KeyCode::Char(character));
+    }
+    match name.to_ascii_lowercase().as_str() {
+        "up" => Some(KeyCode::Up),
+        "down" => Some(KeyCode::Down),
+        "left" => Some(KeyCode::Left),
+        "right" => Some(KeyCode::Right),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BoardSettings {
+    pub width: u16,
+    pub height: u16,
+    /// Size the board to the terminal at startup instead.
+    pub fit_to_terminal: bool,
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Read(io::Error),
+    Syntax {
+        line: usize,
+        message: String,
+    },
+    Board(BoardError),
+    /// The board settings are valid but make a board too small to play on.
+    Unplayable(String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Read(error) => write!(f, "it could not be read: {}", error),
+            Self::Syntax { line, message } => write!(f, "line {}: {}", line, message),
+            Self::Board(error) => write!(f, "{}", error),
+            Self::Unplayable(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+/// Player settings, read from a small subset of TOML: `[section]` headers
+/// and `key = value` lines holding strings, integers, or booleans.
+pub struct Config {
+    path: Option<PathBuf>,
+    pub board: BoardSettings,
+    pub mode: GameMode,
+    pub difficulty: Difficulty,
+    /// The percent chance that newly spawned food is a power-up.
+    pub special_food_chance: u32,
+    pub keys: KeyBindings,
+    pub style: Style,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        let (width, height) = Board::default().size();
+        Self {
+            path: None,
+            board: BoardSettings {
+                width,
+                height,
+                fit_to_terminal: false,
+            },
+            mode: GameMode::default(),
+            difficulty: Difficulty::default(),
+            special_food_chance: DEFAULT_SPECIAL_FOOD_CHANCE,
+            keys: KeyBindings::default(),
+            style: Style::default(),
+        }
+    }
+}
+
+impl Config {
+    /// Loads settings from `path`, keeping the defaults for anything the file
+    /// leaves out, or entirely if the file doesn't exist.
+    pub fn load(path: Option<PathBuf>) -> Result<Self, ConfigError> {
+        let mut config = Self {
+            path,
+            ..Self::default()
+        };
+        let Some(path) = &config.path else {
+            return Ok(config);
+        };
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(config),
+            Err(error) => return Err(ConfigError::Read(error)),
+        };
+        config.apply(&contents)?;
+        config.check_board(None).map_err(ConfigError::Unplayable)?;
+        Ok(config)
+    }
+
+    pub fn path(&self) -> Option<&PathBuf> {
+        self.path.as_ref()
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, self.to_toml())
+    }
+
+    /// Checks that the board settings make a board big enough to play on
+    /// that fits in a `terminal` of the given size, when the size is known.
+    /// Boards fitted to the terminal are sized at startup instead.
+    pub fn check_board(&self, terminal: Option<(usize, usize)>) -> Result<(), String> {
+        if self.board.fit_to_terminal {
+            return Ok(());
+        }
+        let (width, height) = Board::new(self.board.width, self.board.height)
+            .map_err(|error| error.to_string())?
+            .size();
+        if width < MIN_PLAYABLE_WIDTH || height < MIN_PLAYABLE_HEIGHT {
+            return Err(format!(
+                "a {}x{} board is too small to play on, boards must be at least {}x{}",
+                width, height, MIN_PLAYABLE_WIDTH, MIN_PLAYABLE_HEIGHT
+            ));
+        }
+        match terminal {
+            Some((columns, rows)) if usize::from(width) > columns || usize::from(height) > rows => {
+                Err(format!(
+                    "a {}x{} board doesn't fit in your {}x{} terminal",
+                    width, height, columns, rows
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn board(&self) -> Result<Board, BoardError> {
+        if self.board.fit_to_terminal {
+            if let Some((width, height)) = term_size::dimensions() {
+                let clamp = |dimension: usize| {
+                    u16::try_from(dimension)
+                        .unwrap_or(u16::MAX)
+                        .min(board::MAX_BOARD_DIMENSION)
+                };
+                return Board::new(clamp(width), clamp(height));
+            }
+        }
+        Board::new(self.board.width, self.board.height)
+    }
+
+    fn apply(&mut self, contents: &str) -> Result<(), ConfigError> {
+        let mut section = String::new();
+        let mut key_lines = Vec::new();
+        for (index, line) in contents.lines().enumerate() {
+            let error = |message: String| ConfigError::Syntax {
+                line: index + 1,
+                message,
+            };
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(header) = line.strip_prefix('[') {
+                let header = header.split('#').next().unwrap_or_default().trim();
+                section = header
+                    .strip_suffix(']')
+                    .ok_or_else(|| error("section headers must end with `]`".to_string()))?
+                    .trim()
+                    .to_string();
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| error(format!("expected `key = value`, found \"{}\"", line)))?;
+            let value = Value::parse(value.trim()).map_err(error)?;
+            self.set(&section, key.trim(), value).map_err(error)?;
+            if section == "keys" {
+                key_lines.push((key.trim(), index + 1));
+            }
+        }
+        // Clashes can involve keys set on different lines, so they're checked
+        // once every key is in, and reported on the last line involved.
+        self.keys.check().map_err(|clash| ConfigError::Syntax {
+            line: key_lines
+                .iter()
+                .filter(|(key, _)| clash.settings().contains(key))
+                .map(|(_, line)| *line)
+                .max()
+                .unwrap_or_default(),
+            message: clash.to_string(),
+        })
+    }
+
+    fn set(&mut self, section: &str, key: &str, value: Value) -> Result<(), String> {
+        match (section, key) {
+            ("board", "width") => self.board.width = value.dimension()?,
+            ("board", "height") => self.board.height = value.dimension()?,
+            ("board", "fit_to_terminal") => self.board.fit_to_terminal = value.boolean()?,
+            ("game", "mode") => {
+                let name = value.string()?;
+                self.mode = GameMode::parse(&name).ok_or_else(|| {
+                    format!("unknown mode \"{}\", expected classic or walled", name)
+                })?;
+            }
+            ("game", "difficulty") => {
+                let name = value.string()?;
+                self.difficulty = Difficulty::parse(&name).ok_or_else(|| {
+                    format!(
+                        "unknown difficulty \"{}\", expected slow, normal, or fast",
+                        name
+                    )
+                })?;
+            }
+            ("game", "special_food_chance") => self.special_food_chance = value.percentage()?,
+            ("keys", "up") => self.keys.up = value.key()?,
+            ("keys", "down") => self.keys.down = value.key()?,
+            ("keys", "left") => self.keys.left = value.key()?,
+            ("keys", "right") => self.keys.right = value.key()?,
+            ("keys", "vim_keys") => self.keys.vim_keys = value.boolean()?,
+            ("style", key) => {
+                let (name, is_color) = match key.strip_suffix("_color") {
+                    Some(name) => (name, true),
+                    None => (key, false),
+                };
+                let glyph = self
+                    .style
+                    .glyph_mut(name)
+                    .ok_or_else(|| format!("unknown setting \"{}\" in [style]", key))?;
+                if is_color {
+                    glyph.color = value.color()?;
+                } else {
+                    glyph.character = value.character()?;
+                }
+            }
+            _ => return Err(format!("unknown setting \"{}\" in [{}]", key, section)),
+        }
+        Ok(())
+    }
+
+    fn to_toml(&self) -> String {
+        let mut toml = String::new();
+        toml.push_str("[board]\n");
+        toml.push_str(&format!("width = {}\n", self.board.width));
+        toml.push_str(&format!("height = {}\n", self.board.height));
+        toml.push_str(&format!(
+            "fit_to_terminal = {}\n",
+            self.board.fit_to_terminal
+        ));
+        toml.push_str("\n[game]\n");
+        toml.push_str(&format!("mode = {}\n", quote(self.mode.config_name())));
+        toml.push_str(&format!(
+            "difficulty = {}\n",
+            quote(self.difficulty.config_name())
+        ));
+        toml.push_str(&format!(
+            "special_food_chance = {}\n",
+            self.special_food_chance
+        ));
+        toml.push_str("\n[keys]\n");
+        for (name, key) in [
+            ("up", self.keys.up),
+            ("down", self.keys.down),
+            ("left", self.keys.left),
+            ("right", self.keys.right),
+        ] {
+            toml.push_str(&format!("{} = {}\n", name, quote(&key_name(key))));
+        }
+        toml.push_str(&format!("vim_keys = {}\n", self.keys.vim_keys));
+        toml.push_str("\n[style]\n");
+        for (name, glyph) in self.style.glyphs() {
+            toml.push_str(&format!(
+                "{} = {}\n",
+                name,
+                quote(&glyph.character.to_string())
+            ));
+            toml.push_str(&format!(
+                "{}_color = {}\n",
+                name,
+                quote(color_name(glyph.color))
+            ));
+        }
+        toml
+    }
+}
+
+impl Subcontext for Config {}
+
+/// Where the config file lives on this platform, if there is a home to put
+/// it in.
+pub fn default_path() -> Option<PathBuf> {
+    paths::config_dir().map(|dir| dir.join(FILE_NAME))
+}
+
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+enum Value {
+    String(String),
+    Integer(i64),
+    Boolean(bool),
+}
+
+impl Value {
+    fn parse(text: &str) -> Result<Self, String> {
+        if let Some(rest) = text.strip_prefix('"') {
+            let mut string = String::new();
+            let mut characters = rest.chars();
+            loop {
+                match characters.next() {
+                    None => return Err("strings must end with a `\"`".to_string()),
+                    Some('"') => break,
+                    Some('\\') => match characters.next() {
+                        Some(escaped @ ('"' | '\\')) => string.push(escaped),
+                        _ => return Err("only \\\" and \\\\ escapes are supported".to_string()),
+                    },
+                    Some(character) => string.push(character),
+                }
+            }
+            let trailing = characters.as_str().trim();
+            if !trailing.is_empty() && !trailing.starts_with('#') {
+                return Err(format!("unexpected \"{}\" after a string", trailing));
+            }
+            return Ok(Self::String(string));
+        }
+        let text = text.split('#').next().unwrap_or_default().trim();
+        match text {
+            "true" => Ok(Self::Boolean(true)),
+            "false" => Ok(Self::Boolean(false)),
+            _ => text
+                .parse()
+                .map(Self::Integer)
+                .map_err(|_| format!("\"{}\" is not a string, integer, or boolean", text)),
+        }
+    }
+
+    fn string(self) -> Result<String, String> {
+        match self {
+            Self::String(string) => Ok(string),
+            _ => Err("expected a string".to_string()),
+        }
+    }
+
+    fn boolean(self) -> Result<bool, String> {
+        match self {
+            Self::Boolean(boolean) => Ok(boolean),
+            _ => Err("expected true or false".to_string()),
+        }
+    }
+
+    fn dimension(self) -> Result<u16, String> {
+        match self {
+            Self::Integer(integer) => u16::try_from(integer)
+                .map_err(|_| format!("{} is not a valid board dimension", integer)),
+            _ => Err("expected a number".to_string()),
+        }
+    }
+
+    fn percentage(self) -> Result<u32, String> {
+        match self {
+            Self::Integer(integer @ 0..=100) => Ok(integer as u32),
+            Self::Integer(integer) => Err(format!("{} is not between 0 and 100", integer)),
+            _ => Err("expected a number".to_string()),
+        }
+    }
+
+    fn key(self) -> Result<KeyCode, String> {
+        let name = self.string()?;
+        parse_key(&name).ok_or_else(|| {
+            format!(
+                "unknown key \"{}\", expected Up, Down, Left, Right, or a single character",
+                name
+            )
+        })
+    }
+
+    fn color(self) -> Result<Color, String> {
+        let name = self.string()?;
+        parse_color(&name).ok_or_else(|| format!("unknown color \"{}\"", name))
+    }
+
+    fn character(self) -> Result<char, String> {
+        let string = self.string()?;
+        let mut characters = string.chars();
+        match (characters.next(), characters.next()) {
+            (Some(character), None) => Ok(character),
+            _ => Err(format!("\"{}\" must be a single character", string)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(contents: &str) -> Result<Config, ConfigError> {
+        let mut config = Config::default();
+        config.apply(contents)?;
+        Ok(config)
+    }
+
+    fn error_line(contents: &str) -> Option<(usize, String)> {
+        match apply(contents) {
+            Err(ConfigError::Syntax { line, message }) => Some((line, message)),
+            _ => None,
+        }
+    }
+
+    fn sized(width: u16, height: u16) -> Config {
+        let mut config = Config::default();
+        config.board.width = width;
+        config.board.height = height;
+        config
+    }
+
+    // Loads `contents` from a config file of its own, so tests can run in
+    // parallel.
+    fn load(name: &str, contents: &str) -> Result<Config, ConfigError> {
+        let path =
+            std::env::temp_dir().join(format!("console_snek_{}_{}.toml", std::process::id(), name));
+        fs::write(&path, contents).expect("the config is written");
+        let config = Config::load(Some(path.clone()));
+        fs::remove_file(&path).expect("the config is removed");
+        config
+    }
+
+    #[test]
+    fn boards_too_small_to_play_on_are_refused_on_load() {
+        match load("too_small", "[board]\nwidth = 19\nheight = 10\n") {
+            Err(ConfigError::Unplayable(message)) => assert_eq!(
+                message,
+                "a 19x10 board is too small to play on, boards must be at least 20x10"
+            ),
+            _ => panic!("a 19x10 board loaded"),
+        }
+        let config = load("big_enough", "[board]\nwidth = 20\nheight = 10\n")
+            .expect("the board is big enough");
+        assert_eq!((config.board.width, config.board.height), (20, 10));
+    }
+
+    #[test]
+    fn a_missing_config_file_loads_the_defaults() {
+        let path = std::env::temp_dir().join("console_snek_no_such_config.toml");
+        let config = Config::load(Some(path)).expect("the defaults load");
+        assert_eq!(config.board.width, Config::default().board.width);
+    }
+
+    #[test]
+    fn custom_keys_load() {
+        let config = apply("[keys]\nup = \"w\"\ndown = \"x\"\nleft = \"a\"\nright = \"d\"\n")
+            .expect("the keys are valid");
+        assert_eq!(config.keys.up, KeyCode::Char('w'));
+        assert_eq!(config.keys.down, KeyCode::Char('x'));
+    }
+
+    #[test]
+    fn the_games_own_keys_cannot_steer() {
+        assert_eq!(
+            error_line("[keys]\nup = \"w\"\nleft = \"q\"\n"),
+            Some((
+                3,
+                "\"q\" is already used by the game, steer left with another key".to_string()
+            ))
+        );
+        for key in RESERVED_KEYS {
+            assert!(error_line(&format!("[keys]\nup = \"{}\"", key)).is_some());
+        }
+    }
+
+    #[test]
+    fn directions_cannot_share_a_key() {
+        assert_eq!(
+            error_line("[keys]\nup = \"w\"\n\ndown = \"w\"\n"),
+            Some((4, "\"w\" can't steer both up and down".to_string()))
+        );
+        assert_eq!(
+            error_line("[keys]\nup = \"Down\"\n"),
+            Some((2, "\"Down\" can't steer both up and down".to_string()))
+        );
+    }
+
+    #[test]
+    fn vim_keys_cannot_steer_other_directions() {
+        let clash = Some((3, "\"h\" can't steer both up and left".to_string()));
+        assert_eq!(error_line("[keys]\nup = \"h\"\nvim_keys = true\n"), clash);
+        assert_eq!(error_line("[keys]\nvim_keys = true\nup = \"h\"\n"), clash);
+        assert!(apply("[keys]\nleft = \"h\"\nvim_keys = true\n").is_ok());
+    }
+
+    #[test]
+    fn the_second_player_avoids_the_first_players_keys() {
+        let mut first = KeyBindings::default();
+        assert_eq!(KeyBindings::second_player(&first), KeyBindings::wasd());
+        first.up = KeyCode::Char('w');
+        assert_eq!(KeyBindings::second_player(&first), KeyBindings::ijkl());
+        first.vim_keys = true;
+        assert_eq!(KeyBindings::second_player(&first), KeyBindings::numpad());
+        first.down = KeyCode::Char('8');
+        assert_eq!(KeyBindings::second_player(&first), KeyBindings::wasd());
+    }
+
+    #[test]
+    fn layouts_have_short_labels() {
+        assert_eq!(KeyBindings::default().label(), "arrows");
+        assert_eq!(KeyBindings::wasd().label(), "WASD");
+        assert_eq!(KeyBindings::numpad().label(), "8456");
+        let mixed = KeyBindings {
+            up: KeyCode::Char('w'),
+            ..KeyBindings::default()
+        };
+        assert_eq!(mixed.label(), "W/LEFT/DOWN/RIGHT");
+    }
+
+    #[test]
+    fn playable_boards_pass() {
+        assert_eq!(Config::default().check_board(Some((80, 24))), Ok(()));
+        assert_eq!(
+            sized(MIN_PLAYABLE_WIDTH, MIN_PLAYABLE_HEIGHT).check_board(None),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn boards_must_be_big_enough_to_play_on() {
+        assert_eq!(
+            sized(MIN_PLAYABLE_WIDTH - 1, 20).check_board(None),
+            Err("a 19x20 board is too small to play on, boards must be at least 20x10".to_string())
+        );
+        assert!(sized(80, MIN_PLAYABLE_HEIGHT - 1)
+            .check_board(None)
+            .is_err());
+        assert_eq!(
+            sized(0, 20).check_board(None),
+            Err(BoardError::ZeroWidth.to_string())
+        );
+    }
+
+    #[test]
+    fn boards_must_fit_the_terminal() {
+        assert_eq!(
+            sized(81, 20).check_board(Some((80, 24))),
+            Err("a 81x20 board doesn't fit in your 80x24 terminal".to_string())
+        );
+        assert!(sized(80, 25).check_board(Some((80, 24))).is_err());
+        assert_eq!(sized(80, 24).check_board(Some((80, 24))), Ok(()));
+    }
+
+    #[test]
+    fn fitted_boards_are_checked_at_startup() {
+        let mut config = sized(1, 1);
+        config.board.fit_to_terminal = true;
+        assert_eq!(config.check_board(Some((10, 10))), Ok(()));
+    }
+}
@@ -0,0 +1,126 @@
+use std::process::ExitCode;
+use std::sync::{Arc, Mutex};
+
+use wolf_engine::Subcontext;
+
+use crate::summary::EndCause;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ExitCodeMode {
+    #[default]
+    Normal,
+    Outcome,
+}
+
+impl ExitCodeMode {
+    pub fn parse(mode: &str) -> Option<Self> {
+        match mode {
+            "normal" => Some(Self::Normal),
+            "outcome" => Some(Self::Outcome),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ExitOutcome {
+    #[default]
+    Quit,
+    Won,
+    Died,
+}
+
+impl ExitOutcome {
+    pub fn exit_code(self, mode: ExitCodeMode) -> ExitCode {
+        match (mode, self) {
+            (ExitCodeMode::Normal, _) | (_, Self::Quit) => ExitCode::SUCCESS,
+            (ExitCodeMode::Outcome, Self::Won) => ExitCode::from(2),
+            (ExitCodeMode::Outcome, Self::Died) => ExitCode::from(3),
+        }
+    }
+}
+
+impl From<EndCause> for ExitOutcome {
+    fn from(end_cause: EndCause) -> Self {
+        match end_cause {
+            EndCause::BoardFull => Self::Won,
+            EndCause::SelfCollision | EndCause::WallCollision => Self::Died,
+            EndCause::Quit => Self::Quit,
+        }
+    }
+}
+
+// Shared with `main` so the outcome of the last state survives the engine
+// consuming the context.
+#[derive(Clone, Default)]
+pub struct ExitStatus {
+    outcome: Arc<Mutex<ExitOutcome>>,
+}
+
+impl ExitStatus {
+    pub fn set(&self, outcome: ExitOutcome) {
+        *self.outcome.lock().expect("exit status lock poisoned") = outcome;
+    }
+
+    pub fn outcome(&self) -> ExitOutcome {
+        *self.outcome.lock().expect("exit status lock poisoned")
+    }
+}
+
+impl Subcontext for ExitStatus {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn modes_parse() {
+        assert_eq!(ExitCodeMode::parse("normal"), Some(ExitCodeMode::Normal));
+        assert_eq!(ExitCodeMode::parse("outcome"), Some(ExitCodeMode::Outcome));
+        assert_eq!(ExitCodeMode::parse("Outcome"), None);
+    }
+
+    #[test]
+    fn normal_mode_always_succeeds() {
+        for outcome in [ExitOutcome::Quit, ExitOutcome::Won, ExitOutcome::Died] {
+            assert_eq!(outcome.exit_code(ExitCodeMode::Normal), ExitCode::SUCCESS);
+        }
+    }
+
+    #[test]
+    fn outcome_mode_reports_how_the_run_ended() {
+        assert_eq!(
+            ExitOutcome::Quit.exit_code(ExitCodeMode::Outcome),
+            ExitCode::SUCCESS
+        );
+        assert_eq!(
+            ExitOutcome::Won.exit_code(ExitCodeMode::Outcome),
+            ExitCode::from(2)
+        );
+        assert_eq!(
+            ExitOutcome::Died.exit_code(ExitCodeMode::Outcome),
+            ExitCode::from(3)
+        );
+    }
+
+    #[test]
+    fn end_causes_map_to_outcomes() {
+        assert_eq!(ExitOutcome::from(EndCause::BoardFull), ExitOutcome::Won);
+        assert_eq!(
+            ExitOutcome::from(EndCause::SelfCollision),
+            ExitOutcome::Died
+        );
+        assert_eq!(
+            ExitOutcome::from(EndCause::WallCollision),
+            ExitOutcome::Died
+        );
+        assert_eq!(ExitOutcome::from(EndCause::Quit), ExitOutcome::Quit);
+    }
+
+    #[test]
+    fn clones_share_the_outcome() {
+        let status = ExitStatus::default();
+        status.clone().set(ExitOutcome::Died);
+        assert_eq!(status.outcome(), ExitOutcome::Died);
+    }
+}
@@ -0,0 +1,109 @@
+use std::time::Duration;
+
+pub fn format_score(score: u64) -> String {
+    let digits = score.to_string();
+    let mut formatted = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            formatted.push(',');
+        }
+        formatted.push(digit);
+    }
+    formatted
+}
+
+pub fn format_score_capped(score: u64, max_width: usize) -> String {
+    let full = format_score(score);
+    if full.len() <= max_width || score < 1_000 {
+        return full;
+    }
+    let (unit, suffix) = match score {
+        s if s >= 1_000_000_000 => (1_000_000_000, 'B'),
+        s if s >= 1_000_000 => (1_000_000, 'M'),
+        _ => (1_000, 'k'),
+    };
+    let tenths = score / (unit / 10);
+    let abbreviated = format!("{}.{}{}", tenths / 10, tenths % 10, suffix);
+    if abbreviated.len() <= max_width {
+        abbreviated
+    } else {
+        format!("{}{}", score / unit, suffix)
+    }
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{}:{:02}", minutes, seconds)
+    }
+}
+
+// Formats seconds since the Unix epoch as a UTC `YYYY-MM-DD` date.
+pub fn format_date(unix_seconds: u64) -> String {
+    // Howard Hinnant's days-to-civil conversion, with eras starting on
+    // March 1st so leap days fall at the end of each year.
+    let days = (unix_seconds / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scores_get_thousands_separators() {
+        assert_eq!(format_score(0), "0");
+        assert_eq!(format_score(999), "999");
+        assert_eq!(format_score(1_000), "1,000");
+        assert_eq!(format_score(999_999), "999,999");
+        assert_eq!(format_score(1_000_000), "1,000,000");
+    }
+
+    #[test]
+    fn capped_scores_fit_or_abbreviate() {
+        assert_eq!(format_score_capped(999, 3), "999");
+        assert_eq!(format_score_capped(1_000, 5), "1,000");
+        assert_eq!(format_score_capped(12_345, 5), "12.3k");
+        assert_eq!(format_score_capped(1_234_567, 7), "1.2M");
+        assert_eq!(format_score_capped(2_500_000_000, 7), "2.5B");
+        assert_eq!(format_score_capped(123_456, 4), "123k");
+    }
+
+    #[test]
+    fn capped_scores_never_abbreviate_below_a_thousand() {
+        assert_eq!(format_score_capped(999, 1), "999");
+    }
+
+    #[test]
+    fn durations_roll_over_to_minutes_and_hours() {
+        assert_eq!(format_duration(Duration::from_secs(0)), "0:00");
+        assert_eq!(format_duration(Duration::from_secs(59)), "0:59");
+        assert_eq!(format_duration(Duration::from_secs(60)), "1:00");
+        assert_eq!(format_duration(Duration::from_secs(3_599)), "59:59");
+        assert_eq!(format_duration(Duration::from_secs(3_600)), "1:00:00");
+        assert_eq!(format_duration(Duration::from_millis(59_999)), "0:59");
+    }
+
+    #[test]
+    fn dates_are_utc_days() {
+        assert_eq!(format_date(0), "1970-01-01");
+        assert_eq!(format_date(951_782_400), "2000-02-29");
+        assert_eq!(format_date(1_700_000_000), "2023-11-14");
+    }
+}
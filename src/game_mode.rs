@@ -0,0 +1,223 @@
+use std::fmt;
+
+use crate::board::Board;
+use crate::{Vector2, TICK_RATE};
+
+// Every difficulty speeds up by one frame per move for each this many points,
+// down to MIN_MOVE_INTERVAL, which is 30 moves a second.
+const SPEEDUP_SCORE: u32 = 5;
+const MIN_MOVE_INTERVAL: u32 = TICK_RATE / 30;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum GameMode {
+    /// The snake wraps around to the opposite edge of the board.
+    #[default]
+    Classic,
+    /// The outermost ring of the board is a wall that ends the run.
+    Walled,
+}
+
+impl GameMode {
+    pub const ALL: [GameMode; 2] = [GameMode::Classic, GameMode::Walled];
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|mode| mode.config_name() == name)
+    }
+
+    /// The name used for this mode in the config file.
+    pub fn config_name(self) -> &'static str {
+        match self {
+            Self::Classic => "classic",
+            Self::Walled => "walled",
+        }
+    }
+
+    pub fn is_wall(self, board: &Board, location: Vector2) -> bool {
+        self == GameMode::Walled && board.edge_distance(location) <= 0
+    }
+
+    /// Moves `location` by `velocity`, wrapping around the board in classic
+    /// mode.  In walled mode the result may land on or past the wall.
+    pub fn step(self, board: &Board, location: Vector2, velocity: Vector2) -> Vector2 {
+        let next = Vector2::new(location.x + velocity.x, location.y + velocity.y);
+        match self {
+            GameMode::Classic => board.wrap(next),
+            GameMode::Walled => next,
+        }
+    }
+
+    pub fn spawn_location(self) -> Vector2 {
+        match self {
+            GameMode::Classic => Vector2::new(0, 1),
+            GameMode::Walled => Vector2::new(1, 1),
+        }
+    }
+
+    /// Where a second snake starts, in the corner opposite the first.
+    pub fn opposite_spawn_location(self, board: &Board) -> Vector2 {
+        match self {
+            GameMode::Classic => Vector2::new(board.width() - 1, board.height() - 1),
+            GameMode::Walled => Vector2::new(board.width() - 2, board.height() - 2),
+        }
+    }
+}
+
+impl fmt::Display for GameMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Classic => "Classic (wrap)",
+            Self::Walled => "Walled",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Difficulty {
+    Slow,
+    #[default]
+    Normal,
+    Fast,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [Difficulty::Slow, Difficulty::Normal, Difficulty::Fast];
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|difficulty| difficulty.config_name() == name)
+    }
+
+    /// The name used for this difficulty in the config file.
+    pub fn config_name(self) -> &'static str {
+        match self {
+            Self::Slow => "slow",
+            Self::Normal => "normal",
+            Self::Fast => "fast",
+        }
+    }
+
+    /// How many frames pass between moves at the start of a run: 6, 10 or
+    /// 15 moves a second.
+    pub fn starting_interval(self) -> u32 {
+        match self {
+            Difficulty::Slow => TICK_RATE / 6,
+            Difficulty::Normal => TICK_RATE / 10,
+            Difficulty::Fast => TICK_RATE / 15,
+        }
+    }
+
+    /// How many frames pass between moves once `score` points are scored.
+    pub fn move_interval(self, score: u32) -> u32 {
+        self.starting_interval()
+            .saturating_sub(score / SPEEDUP_SCORE)
+            .max(MIN_MOVE_INTERVAL)
+    }
+
+    /// How many frames pass between moves with a speed-up active, which
+    /// halves the interval but never goes below the fastest normal speed.
+    pub fn sped_up_interval(self, score: u32) -> u32 {
+        (self.move_interval(score) / 2).max(MIN_MOVE_INTERVAL)
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Slow => "Slow",
+            Self::Normal => "Normal",
+            Self::Fast => "Fast",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn board() -> Board {
+        Board::new(20, 10).unwrap()
+    }
+
+    #[test]
+    fn only_walled_mode_has_walls() {
+        for location in [Vector2::new(0, 4), Vector2::new(19, 4), Vector2::new(5, 9)] {
+            assert!(GameMode::Walled.is_wall(&board(), location));
+            assert!(!GameMode::Classic.is_wall(&board(), location));
+        }
+        assert!(!GameMode::Walled.is_wall(&board(), Vector2::new(1, 1)));
+        assert!(GameMode::Walled.is_wall(&board(), Vector2::new(-1, 4)));
+    }
+
+    #[test]
+    fn classic_steps_wrap_around_the_board() {
+        let step = |location, velocity| GameMode::Classic.step(&board(), location, velocity);
+        assert_eq!(
+            step(Vector2::new(19, 3), Vector2::new(1, 0)),
+            Vector2::new(0, 3)
+        );
+        assert_eq!(
+            step(Vector2::new(0, 3), Vector2::new(-1, 0)),
+            Vector2::new(19, 3)
+        );
+        assert_eq!(
+            step(Vector2::new(4, 0), Vector2::new(0, -1)),
+            Vector2::new(4, 9)
+        );
+        assert_eq!(
+            step(Vector2::new(4, 9), Vector2::new(0, 1)),
+            Vector2::new(4, 0)
+        );
+        assert_eq!(
+            step(Vector2::new(4, 4), Vector2::new(0, 1)),
+            Vector2::new(4, 5)
+        );
+    }
+
+    #[test]
+    fn walled_steps_run_into_the_wall() {
+        let step = |location, velocity| GameMode::Walled.step(&board(), location, velocity);
+        assert_eq!(
+            step(Vector2::new(19, 3), Vector2::new(1, 0)),
+            Vector2::new(20, 3)
+        );
+        assert_eq!(
+            step(Vector2::new(1, 1), Vector2::new(0, -1)),
+            Vector2::new(1, 0)
+        );
+    }
+
+    #[test]
+    fn difficulties_start_at_their_moves_per_second() {
+        let moves_per_second = |difficulty: Difficulty| TICK_RATE / difficulty.starting_interval();
+        assert_eq!(moves_per_second(Difficulty::Slow), 6);
+        assert_eq!(moves_per_second(Difficulty::Normal), 10);
+        assert_eq!(moves_per_second(Difficulty::Fast), 15);
+    }
+
+    #[test]
+    fn moves_speed_up_with_the_score() {
+        for difficulty in Difficulty::ALL {
+            let start = difficulty.starting_interval();
+            assert_eq!(difficulty.move_interval(0), start);
+            assert_eq!(difficulty.move_interval(SPEEDUP_SCORE - 1), start);
+            assert_eq!(difficulty.move_interval(SPEEDUP_SCORE), start - 1);
+            assert_eq!(difficulty.move_interval(u32::MAX), MIN_MOVE_INTERVAL);
+        }
+    }
+
+    #[test]
+    fn speed_ups_halve_the_interval_down_to_the_minimum() {
+        assert_eq!(Difficulty::Slow.sped_up_interval(0), 5);
+        assert_eq!(Difficulty::Normal.sped_up_interval(0), 3);
+        assert_eq!(Difficulty::Fast.sped_up_interval(0), MIN_MOVE_INTERVAL);
+        assert_eq!(
+            Difficulty::Slow.sped_up_interval(u32::MAX),
+            MIN_MOVE_INTERVAL
+        );
+    }
+}
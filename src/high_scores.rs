@@ -0,0 +1,371 @@
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use log::*;
+use wolf_engine::Subcontext;
+
+use crate::paths;
+
+pub const MAX_ENTRIES: usize = 10;
+pub const NAME_LENGTH: usize = 3;
+
+const FILE_NAME: &str = "scores.txt";
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct HighScore {
+    pub name: String,
+    pub score: u32,
+    /// The snake's length when the run ended, or 0 for entries saved before
+    /// lengths were recorded.
+    pub length: usize,
+    /// When the run ended, in seconds since the Unix epoch, or 0 if unknown.
+    pub recorded_at: u64,
+}
+
+impl HighScore {
+    pub fn new(name: &str, score: u32, length: usize) -> Self {
+        Self {
+            name: name.to_string(),
+            score,
+            length,
+            recorded_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs()),
+        }
+    }
+}
+
+/// The top scores, best first, kept in a plain text file with one
+/// `<score> <length> <recorded_at> <name>` entry per line.  Seeded runs can
+/// be played until the food falls just right, so each seed has a table of
+/// its own, saved as entries prefixed with `seed <seed>`.
+pub struct HighScores {
+    path: Option<PathBuf>,
+    /// The main table under `None`, and each seed's under its seed.
+    tables: BTreeMap<Option<u64>, Vec<HighScore>>,
+}
+
+impl HighScores {
+    /// Loads the table from `path`, falling back to an empty table when the
+    /// file is missing or can't be read.  Without a path nothing is saved.
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let tables = match &path {
+            Some(path) => match fs::read_to_string(path) {
+                Ok(contents) => parse_entries(&contents).unwrap_or_else(|| {
+                    warn!("Ignoring corrupt high score file at {}", path.display());
+                    BTreeMap::new()
+                }),
+                Err(error) if error.kind() == io::ErrorKind::NotFound => BTreeMap::new(),
+                Err(error) => {
+                    warn!(
+                        "Failed to read high scores from {}: {}",
+                        path.display(),
+                        error
+                    );
+                    BTreeMap::new()
+                }
+            },
+            None => BTreeMap::new(),
+        };
+        Self { path, tables }
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let contents: String = self
+            .tables
+            .iter()
+            .flat_map(|(seed, entries)| {
+                let prefix = seed.map_or(String::new(), |seed| format!("seed {} ", seed));
+                entries.iter().map(move |entry| {
+                    format!(
+                        "{}{} {} {} {}\n",
+                        prefix, entry.score, entry.length, entry.recorded_at, entry.name
+                    )
+                })
+            })
+            .collect();
+        fs::write(path, contents)
+    }
+
+    /// The table for runs with `seed`, or the main table for `None`.
+    pub fn entries(&self, seed: Option<u64>) -> &[HighScore] {
+        self.tables.get(&seed).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn qualifies(&self, seed: Option<u64>, score: u32) -> bool {
+        let entries = self.entries(seed);
+        score > 0
+            && (entries.len() < MAX_ENTRIES
+                || entries.last().is_some_and(|entry| score > entry.score))
+    }
+
+    /// Inserts an entry into `seed`'s table below any with an equal score
+    /// and returns its rank, or `None` if it didn't make the table.
+    pub fn insert(&mut self, seed: Option<u64>, high_score: HighScore) -> Option<usize> {
+        if !self.qualifies(seed, high_score.score) {
+            return None;
+        }
+        let entries = self.tables.entry(seed).or_default();
+        let rank = entries
+            .iter()
+            .position(|entry| high_score.score > entry.score)
+            .unwrap_or(entries.len());
+        entries.insert(rank, high_score);
+        entries.truncate(MAX_ENTRIES);
+        Some(rank)
+    }
+}
+
+impl Subcontext for HighScores {}
+
+/// Where the high score file lives on this platform, if there is a home to
+/// put it in.
+pub fn default_path() -> Option<PathBuf> {
+    paths::data_dir().map(|dir| dir.join(FILE_NAME))
+}
+
+pub fn validate_name(name: &str) -> Result<(), String> {
+    if name.is_empty() {
+        return Err("Enter at least one letter".to_string());
+    }
+    if !name
+        .chars()
+        .all(|character| character.is_ascii_alphabetic())
+    {
+        return Err("Letters only".to_string());
+    }
+    Ok(())
+}
+
+fn parse_entries(contents: &str) -> Option<BTreeMap<Option<u64>, Vec<HighScore>>> {
+    let mut tables: BTreeMap<Option<u64>, Vec<HighScore>> = BTreeMap::new();
+    for line in contents.lines().filter(|line| !line.trim().is_empty()) {
+        let (seed, fields) = match line.split_whitespace().collect::<Vec<&str>>()[..] {
+            ["seed", seed, ref fields @ ..] => (Some(seed.parse().ok()?), fields.to_vec()),
+            ref fields => (None, fields.to_vec()),
+        };
+        // Entries saved before lengths and dates were recorded only have a
+        // score and a name.
+        let (score, length, recorded_at, name) = match fields[..] {
+            [score, name] if seed.is_none() => (score, "0", "0", name),
+            [score, length, recorded_at, name] => (score, length, recorded_at, name),
+            _ => return None,
+        };
+        validate_name(name).ok()?;
+        tables.entry(seed).or_default().push(HighScore {
+            name: name.to_string(),
+            score: score.parse().ok()?,
+            length: length.parse().ok()?,
+            recorded_at: recorded_at.parse().ok()?,
+        });
+    }
+    for entries in tables.values_mut() {
+        entries.sort_by_key(|entry| Reverse(entry.score));
+        entries.truncate(MAX_ENTRIES);
+    }
+    Some(tables)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process;
+
+    // A score file of its own for each test, so tests can run in parallel.
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "console_snek_{}_{}_{}",
+            process::id(),
+            name,
+            FILE_NAME
+        ))
+    }
+
+    fn entry(name: &str, score: u32) -> HighScore {
+        HighScore {
+            name: name.to_string(),
+            score,
+            length: score as usize + 1,
+            recorded_at: 1_700_000_000,
+        }
+    }
+
+    fn table(scores: &[u32]) -> HighScores {
+        let mut high_scores = HighScores::load(None);
+        for score in scores {
+            high_scores.insert(None, entry("AAA", *score));
+        }
+        high_scores
+    }
+
+    fn scores(high_scores: &HighScores) -> Vec<u32> {
+        high_scores
+            .entries(None)
+            .iter()
+            .map(|entry| entry.score)
+            .collect()
+    }
+
+    #[test]
+    fn saved_scores_load_back() {
+        let path = temp_path("round_trip");
+        let mut high_scores = HighScores::load(Some(path.clone()));
+        high_scores.insert(None, entry("ABC", 12));
+        high_scores.insert(None, entry("XYZ", 30));
+        high_scores.save().expect("the scores save");
+
+        let loaded = HighScores::load(Some(path.clone()));
+        fs::remove_file(&path).expect("the score file is removed");
+        assert_eq!(loaded.entries(None), [entry("XYZ", 30), entry("ABC", 12)]);
+    }
+
+    #[test]
+    fn a_missing_file_loads_an_empty_table() {
+        let high_scores = HighScores::load(Some(temp_path("missing")));
+        assert!(high_scores.entries(None).is_empty());
+    }
+
+    #[test]
+    fn a_corrupt_file_loads_an_empty_table() {
+        let path = temp_path("corrupt");
+        fs::write(&path, "30 4 0 ABC\nnot a score\n").expect("the score file is written");
+        let high_scores = HighScores::load(Some(path.clone()));
+        fs::remove_file(&path).expect("the score file is removed");
+        assert!(high_scores.entries(None).is_empty());
+    }
+
+    #[test]
+    fn entries_with_bad_names_are_corrupt() {
+        assert_eq!(parse_entries("30 4 0 A1C\n"), None);
+        assert_eq!(parse_entries("30 4 0\n"), None);
+        assert_eq!(parse_entries("-3 4 0 ABC\n"), None);
+    }
+
+    #[test]
+    fn legacy_entries_have_no_length_or_date() {
+        let tables = parse_entries("12 ABC\n\n30 31 1700000000 XYZ\n").expect("the file parses");
+        assert_eq!(
+            tables[&None],
+            [
+                entry("XYZ", 30),
+                HighScore {
+                    name: "ABC".to_string(),
+                    score: 12,
+                    length: 0,
+                    recorded_at: 0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn entries_are_kept_best_first() {
+        let mut high_scores = table(&[5, 20, 10]);
+        assert_eq!(scores(&high_scores), [20, 10, 5]);
+        assert_eq!(high_scores.insert(None, entry("NEW", 15)), Some(1));
+        assert_eq!(scores(&high_scores), [20, 15, 10, 5]);
+    }
+
+    #[test]
+    fn ties_rank_below_the_earlier_entry() {
+        let mut high_scores = table(&[20, 10]);
+        assert_eq!(high_scores.insert(None, entry("NEW", 10)), Some(2));
+        assert_eq!(high_scores.entries(None)[1].name, "AAA");
+        assert_eq!(high_scores.entries(None)[2].name, "NEW");
+    }
+
+    #[test]
+    fn zero_never_qualifies() {
+        let mut high_scores = table(&[]);
+        assert!(!high_scores.qualifies(None, 0));
+        assert_eq!(high_scores.insert(None, entry("NEW", 0)), None);
+        assert!(high_scores.entries(None).is_empty());
+    }
+
+    #[test]
+    fn a_full_table_keeps_the_top_entries() {
+        let mut high_scores = table(&[10, 20, 30, 40, 50, 60, 70, 80, 90, 100]);
+        assert_eq!(high_scores.entries(None).len(), MAX_ENTRIES);
+        assert!(!high_scores.qualifies(None, 10));
+        assert_eq!(high_scores.insert(None, entry("NEW", 10)), None);
+        assert!(high_scores.qualifies(None, 11));
+        assert_eq!(high_scores.insert(None, entry("NEW", 11)), Some(9));
+        assert_eq!(high_scores.entries(None).len(), MAX_ENTRIES);
+        assert_eq!(high_scores.entries(None)[9], entry("NEW", 11));
+    }
+
+    #[test]
+    fn long_files_are_cut_to_the_table_size() {
+        let contents: String = (1..=12).map(|score| format!("{} AAA\n", score)).collect();
+        let tables = parse_entries(&contents).expect("the file parses");
+        let entries = &tables[&None];
+        assert_eq!(entries.len(), MAX_ENTRIES);
+        assert_eq!(entries[0].score, 12);
+        assert_eq!(entries[9].score, 3);
+    }
+
+    #[test]
+    fn names_must_be_letters() {
+        assert!(validate_name("abc").is_ok());
+        assert_eq!(
+            validate_name(""),
+            Err("Enter at least one letter".to_string())
+        );
+        assert_eq!(validate_name("A1"), Err("Letters only".to_string()));
+    }
+
+    #[test]
+    fn each_seed_has_a_table_of_its_own() {
+        let mut high_scores = table(&[10, 20, 30, 40, 50, 60, 70, 80, 90, 100]);
+        assert!(!high_scores.qualifies(None, 5));
+        assert!(high_scores.qualifies(Some(7), 5));
+        assert_eq!(high_scores.insert(Some(7), entry("SED", 5)), Some(0));
+        assert_eq!(high_scores.insert(Some(7), entry("TOP", 8)), Some(0));
+        assert_eq!(high_scores.insert(Some(9), entry("ONE", 1)), Some(0));
+        assert_eq!(scores(&high_scores).len(), MAX_ENTRIES);
+        assert!(!high_scores.entries(None).contains(&entry("SED", 5)));
+        assert_eq!(
+            high_scores.entries(Some(7)),
+            [entry("TOP", 8), entry("SED", 5)]
+        );
+        assert_eq!(high_scores.entries(Some(9)), [entry("ONE", 1)]);
+        assert!(high_scores.entries(Some(11)).is_empty());
+    }
+
+    #[test]
+    fn seeded_tables_load_back() {
+        let path = temp_path("seeded");
+        let mut high_scores = HighScores::load(Some(path.clone()));
+        high_scores.insert(None, entry("ABC", 12));
+        high_scores.insert(Some(u64::MAX), entry("XYZ", 30));
+        high_scores.insert(Some(3), entry("DEF", 4));
+        high_scores.save().expect("the scores save");
+
+        let contents = fs::read_to_string(&path).expect("the score file is read");
+        let loaded = HighScores::load(Some(path.clone()));
+        fs::remove_file(&path).expect("the score file is removed");
+        assert_eq!(
+            contents,
+            "12 13 1700000000 ABC\nseed 3 4 5 1700000000 DEF\nseed 18446744073709551615 30 31 1700000000 XYZ\n"
+        );
+        assert_eq!(loaded.entries(None), [entry("ABC", 12)]);
+        assert_eq!(loaded.entries(Some(u64::MAX)), [entry("XYZ", 30)]);
+        assert_eq!(loaded.entries(Some(3)), [entry("DEF", 4)]);
+    }
+
+    #[test]
+    fn seeded_entries_need_every_field() {
+        assert_eq!(parse_entries("seed 3 12 ABC\n"), None);
+        assert_eq!(parse_entries("seed x 12 13 0 ABC\n"), None);
+    }
+}
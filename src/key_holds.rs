@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use console_engine::KeyCode;
+
+#[derive(Clone, Copy)]
+struct Hold {
+    since: u64,
+    last_seen: u64,
+}
+
+/// Tracks held keys on the frame clock.  Terminals report presses but not
+/// releases, and a held key comes through as the same press repeated, so a
+/// hold starts on the frame a key's press is first seen and lasts until it
+/// hasn't been seen for a tenth of a second.
+pub struct KeyHolds {
+    frame_duration: Duration,
+    release_frames: u64,
+    frame: u64,
+    holds: HashMap<KeyCode, Hold>,
+}
+
+impl KeyHolds {
+    pub fn new(frame_rate: u32) -> Self {
+        Self {
+            frame_duration: Duration::from_secs(1) / frame_rate.max(1),
+            release_frames: u64::from(frame_rate / 10),
+            frame: 0,
+            holds: HashMap::new(),
+        }
+    }
+
+    /// Starts a new frame, on which the keys in `pressed` were seen.
+    pub fn next_frame(&mut self, pressed: impl IntoIterator<Item = KeyCode>) {
+        self.frame += 1;
+        let frame = self.frame;
+        for key in pressed {
+            self.holds
+                .entry(key)
+                .or_insert(Hold {
+                    since: frame,
+                    last_seen: frame,
+                })
+                .last_seen = frame;
+        }
+        let release_frames = self.release_frames;
+        self.holds
+            .retain(|_, hold| frame - hold.last_seen <= release_frames);
+    }
+
+    /// How many frames `key` has been held since the frame it was pressed,
+    /// or `None` if it's up.
+    pub fn held_frames(&self, key: KeyCode) -> Option<u64> {
+        self.holds.get(&key).map(|hold| self.frame - hold.since)
+    }
+
+    pub fn held_duration(&self, key: KeyCode) -> Option<Duration> {
+        self.held_frames(key)
+            .map(|frames| self.frame_duration * frames as u32)
+    }
+
+    /// Whether a held `key` should act on this frame: on the press, then once
+    /// every `interval` frames after it's been held for `delay` frames.
+    pub fn is_repeating(&self, key: KeyCode, delay: u64, interval: u64) -> bool {
+        match self.held_frames(key) {
+            Some(0) => true,
+            Some(frames) => frames >= delay && (frames - delay).is_multiple_of(interval.max(1)),
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: KeyCode = KeyCode::Up;
+
+    // Runs one frame per entry in `frames`, with KEY seen on the `true` ones.
+    fn run(holds: &mut KeyHolds, frames: &[bool]) {
+        for seen in frames {
+            holds.next_frame(seen.then_some(KEY));
+        }
+    }
+
+    #[test]
+    fn a_key_that_was_never_pressed_is_not_held() {
+        let mut holds = KeyHolds::new(60);
+        run(&mut holds, &[false, false]);
+        assert_eq!(holds.held_frames(KEY), None);
+        assert_eq!(holds.held_duration(KEY), None);
+    }
+
+    #[test]
+    fn holds_count_from_the_press() {
+        let mut holds = KeyHolds::new(10);
+        run(&mut holds, &[false, true]);
+        assert_eq!(holds.held_duration(KEY), Some(Duration::ZERO));
+        run(&mut holds, &[true, true, true]);
+        assert_eq!(holds.held_frames(KEY), Some(3));
+        assert_eq!(holds.held_duration(KEY), Some(Duration::from_millis(300)));
+    }
+
+    #[test]
+    fn holds_last_through_the_gaps_between_repeats() {
+        let mut holds = KeyHolds::new(60);
+        run(&mut holds, &[true, false, false, true, false, false]);
+        assert_eq!(holds.held_frames(KEY), Some(5));
+    }
+
+    #[test]
+    fn releasing_ends_the_hold() {
+        let mut holds = KeyHolds::new(60);
+        run(&mut holds, &[true, true]);
+        run(&mut holds, &[false; 6]);
+        assert_eq!(holds.held_frames(KEY), Some(7));
+        run(&mut holds, &[false]);
+        assert_eq!(holds.held_frames(KEY), None);
+        run(&mut holds, &[true]);
+        assert_eq!(holds.held_frames(KEY), Some(0));
+    }
+
+    #[test]
+    fn other_keys_are_tracked_separately() {
+        let mut holds = KeyHolds::new(60);
+        holds.next_frame([KeyCode::Up]);
+        holds.next_frame([KeyCode::Up, KeyCode::Down]);
+        assert_eq!(holds.held_frames(KeyCode::Up), Some(1));
+        assert_eq!(holds.held_frames(KeyCode::Down), Some(0));
+    }
+
+    #[test]
+    fn held_keys_repeat_after_the_delay() {
+        let mut holds = KeyHolds::new(60);
+        let mut repeats = Vec::new();
+        for _ in 0..8 {
+            run(&mut holds, &[true]);
+            repeats.push(holds.is_repeating(KEY, 3, 2));
+        }
+        assert_eq!(
+            repeats,
+            [true, false, false, true, false, true, false, true]
+        );
+        run(&mut holds, &[false; 11]);
+        assert!(!holds.is_repeating(KEY, 3, 2));
+    }
+}
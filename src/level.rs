@@ -0,0 +1,314 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use log::*;
+use wolf_engine::Subcontext;
+
+use crate::board::Board;
+use crate::{paths, Vector2};
+
+pub const DEFAULT_GOAL: u32 = 10;
+
+const DIRECTORY_NAME: &str = "levels";
+const FILE_EXTENSION: &str = "txt";
+
+/// A map loaded from a level file.  Files start with optional `name:` and
+/// `goal:` lines, followed by the map itself: `#` for walls, `@` for the
+/// snake's spawn point, `f` for cells food may spawn in (anywhere free if
+/// there are none), and `.` or spaces for open floor.  The map's first row
+/// sits just below the HUD.
+pub struct Level {
+    pub name: String,
+    /// Points to score on this level before moving on to the next one.
+    pub goal: u32,
+    walls: HashSet<Vector2>,
+    food_cells: HashSet<Vector2>,
+    spawn: Option<Vector2>,
+}
+
+impl Level {
+    pub fn parse(name: &str, contents: &str, board: &Board) -> Result<Self, String> {
+        let mut level = Self {
+            name: name.to_string(),
+            goal: DEFAULT_GOAL,
+            walls: HashSet::new(),
+            food_cells: HashSet::new(),
+            spawn: None,
+        };
+        let mut lines = contents.lines().peekable();
+        while let Some((key, value)) = lines.peek().and_then(|line| line.split_once(':')) {
+            match key.trim() {
+                "name" => level.name = value.trim().to_string(),
+                "goal" => {
+                    level.goal = value
+                        .trim()
+                        .parse()
+                        .map_err(|_| format!("\"{}\" is not a valid goal", value.trim()))?
+                }
+                other => return Err(format!("unknown setting \"{}\"", other)),
+            }
+            lines.next();
+        }
+
+        for (y, line) in (1..).zip(lines) {
+            for (x, character) in (0..).zip(line.chars()) {
+                let location = Vector2::new(x, y);
+                if matches!(character, '.' | ' ') {
+                    continue;
+                }
+                if x >= board.width() || y >= board.height() {
+                    return Err(format!(
+                        "the map doesn't fit on a {}x{} board",
+                        board.width(),
+                        board.height()
+                    ));
+                }
+                match character {
+                    '#' => {
+                        level.walls.insert(location);
+                    }
+                    'f' => {
+                        level.food_cells.insert(location);
+                    }
+                    '@' if level.spawn.is_some() => {
+                        return Err("the map has more than one spawn point".to_string())
+                    }
+                    '@' => level.spawn = Some(location),
+                    other => return Err(format!("unknown map character '{}'", other)),
+                }
+            }
+        }
+        if level
+            .spawn
+            .is_some_and(|spawn| board.edge_distance(spawn) <= 0)
+        {
+            return Err(
+                "the spawn point is on the board's edge, which is a wall in walled mode"
+                    .to_string(),
+            );
+        }
+        Ok(level)
+    }
+
+    pub fn is_wall(&self, location: Vector2) -> bool {
+        self.walls.contains(&location)
+    }
+
+    pub fn walls(&self) -> impl Iterator<Item = &Vector2> {
+        self.walls.iter()
+    }
+
+    pub fn allows_food(&self, location: Vector2) -> bool {
+        self.food_cells.is_empty() || self.food_cells.contains(&location)
+    }
+
+    pub fn spawn(&self) -> Option<Vector2> {
+        self.spawn
+    }
+}
+
+/// The levels found at startup, played in file name order.
+#[derive(Clone)]
+pub struct Campaign {
+    levels: Arc<[Level]>,
+    index: usize,
+}
+
+impl Campaign {
+    pub fn new(levels: Vec<Level>) -> Option<Self> {
+        if levels.is_empty() {
+            return None;
+        }
+        Some(Self {
+            levels: levels.into(),
+            index: 0,
+        })
+    }
+
+    pub fn level(&self) -> &Level {
+        &self.levels[self.index]
+    }
+
+    /// The 1-based number of the current level.
+    pub fn number(&self) -> usize {
+        self.index + 1
+    }
+
+    pub fn level_count(&self) -> usize {
+        self.levels.len()
+    }
+
+    pub fn first(&self) -> Self {
+        Self {
+            levels: self.levels.clone(),
+            index: 0,
+        }
+    }
+
+    pub fn next(&self) -> Option<Self> {
+        (self.index + 1 < self.levels.len()).then(|| Self {
+            levels: self.levels.clone(),
+            index: self.index + 1,
+        })
+    }
+}
+
+impl Subcontext for Campaign {}
+
+/// Looks for levels in `levels/` under the working directory, then under the
+/// data directory.
+pub fn default_dir() -> Option<PathBuf> {
+    let local = PathBuf::from(DIRECTORY_NAME);
+    if local.is_dir() {
+        return Some(local);
+    }
+    paths::data_dir().map(|dir| dir.join(DIRECTORY_NAME))
+}
+
+/// Loads every level file in `dir`, skipping any that can't be read or
+/// parsed with a warning.
+pub fn load_levels(dir: &Path, board: &Board) -> Vec<Level> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Vec::new(),
+        Err(error) => {
+            warn!("Failed to read levels from {}: {}", dir.display(), error);
+            return Vec::new();
+        }
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == FILE_EXTENSION)
+        })
+        .collect();
+    paths.sort();
+    paths
+        .iter()
+        .filter_map(|path| {
+            let name = path.file_stem()?.to_string_lossy();
+            let level = fs::read_to_string(path)
+                .map_err(|error| error.to_string())
+                .and_then(|contents| Level::parse(&name, &contents, board));
+            match level {
+                Ok(level) => Some(level),
+                Err(error) => {
+                    warn!("Skipping level {}: {}", path.display(), error);
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn board() -> Board {
+        Board::new(20, 10).unwrap()
+    }
+
+    fn parse(contents: &str) -> Result<Level, String> {
+        Level::parse("test", contents, &board())
+    }
+
+    fn campaign(names: &[&str]) -> Campaign {
+        let levels = names
+            .iter()
+            .map(|name| Level::parse(name, "", &board()).unwrap())
+            .collect();
+        Campaign::new(levels).expect("there are levels")
+    }
+
+    #[test]
+    fn maps_are_read_below_the_hud() {
+        let level = parse("..#\n.@f\n#").expect("the level is valid");
+        assert_eq!(level.name, "test");
+        assert_eq!(level.goal, DEFAULT_GOAL);
+        let mut walls: Vec<Vector2> = level.walls().copied().collect();
+        walls.sort_by_key(|wall| (wall.y, wall.x));
+        assert_eq!(walls, [Vector2::new(2, 1), Vector2::new(0, 3)]);
+        assert_eq!(level.spawn(), Some(Vector2::new(1, 2)));
+        assert!(level.allows_food(Vector2::new(2, 2)));
+        assert!(!level.allows_food(Vector2::new(3, 3)));
+    }
+
+    #[test]
+    fn food_may_go_anywhere_without_food_cells() {
+        let level = parse("#").expect("the level is valid");
+        assert!(level.allows_food(Vector2::new(5, 5)));
+        assert_eq!(level.spawn(), None);
+    }
+
+    #[test]
+    fn the_header_names_the_level_and_sets_its_goal() {
+        let level = parse("name: The Maze\ngoal: 25\n#.#").expect("the level is valid");
+        assert_eq!(level.name, "The Maze");
+        assert_eq!(level.goal, 25);
+        assert!(level.is_wall(Vector2::new(0, 1)));
+        assert_eq!(
+            parse("goal: lots\n#").err(),
+            Some("\"lots\" is not a valid goal".to_string())
+        );
+        assert_eq!(
+            parse("speed: 3\n#").err(),
+            Some("unknown setting \"speed\"".to_string())
+        );
+    }
+
+    #[test]
+    fn unknown_map_characters_are_refused() {
+        assert_eq!(
+            parse("..x").err(),
+            Some("unknown map character 'x'".to_string())
+        );
+    }
+
+    #[test]
+    fn maps_have_at_most_one_spawn_point() {
+        assert_eq!(
+            parse(".@\n.@").err(),
+            Some("the map has more than one spawn point".to_string())
+        );
+    }
+
+    #[test]
+    fn spawn_points_on_the_edge_are_refused() {
+        let refused = Some(
+            "the spawn point is on the board's edge, which is a wall in walled mode".to_string(),
+        );
+        assert_eq!(parse("@").err(), refused);
+        assert_eq!(parse(&format!("{}@", ".".repeat(19))).err(), refused);
+        assert_eq!(parse(&format!("{}\n.@", "\n".repeat(7))).err(), refused);
+        assert!(parse(&format!("{}\n.@", "\n".repeat(6))).is_ok());
+    }
+
+    #[test]
+    fn maps_must_fit_on_the_board() {
+        let too_small = Some("the map doesn't fit on a 20x10 board".to_string());
+        assert_eq!(parse(&"#".repeat(21)).err(), too_small);
+        assert_eq!(parse(&"#\n".repeat(10)).err(), too_small);
+        assert!(parse(&"#\n".repeat(9)).is_ok());
+        // Open floor past the edge doesn't matter.
+        assert!(parse(&format!("#{}", ".".repeat(30))).is_ok());
+    }
+
+    #[test]
+    fn campaigns_play_their_levels_in_order() {
+        assert!(Campaign::new(Vec::new()).is_none());
+        let first = campaign(&["one", "two", "three"]);
+        assert_eq!((first.number(), first.level().name.as_str()), (1, "one"));
+        let second = first.next().expect("there is a second level");
+        assert_eq!((second.number(), second.level().name.as_str()), (2, "two"));
+        let third = second.next().expect("there is a third level");
+        assert_eq!(third.level().name, "three");
+        assert!(third.next().is_none());
+        assert_eq!(third.first().number(), 1);
+        assert_eq!(third.level_count(), 3);
+    }
+}
@@ -0,0 +1,3113 @@
+//! Console Snek, the terminal snake game.  The `console_snek` binary plays
+//! it with [`run`], and [`SnekApp`] lets another app's event loop drive it.
+
+mod app;
+mod board;
+mod cli;
+mod config;
+mod exit;
+mod format;
+mod game_mode;
+mod high_scores;
+mod key_holds;
+mod level;
+mod outline;
+mod paths;
+mod play_style;
+mod prompt;
+mod quit;
+mod replay;
+mod screen;
+mod seed;
+mod self_test;
+mod startup;
+mod state_hash;
+mod summary;
+
+use std::collections::{HashSet, VecDeque};
+use std::env;
+use std::fmt;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Duration;
+
+use console_engine::events::Event;
+use console_engine::pixel::Pixel;
+use console_engine::*;
+use log::*;
+use rand::prelude::*;
+use wolf_engine::*;
+
+pub use crate::app::{Renderer, SnekApp};
+pub use crate::config::Config;
+
+use crate::board::Board;
+use crate::cli::Options;
+use crate::config::{ConfigError, Direction, Glyph, KeyBindings, Style};
+use crate::exit::{ExitOutcome, ExitStatus};
+use crate::format::{format_date, format_duration, format_score, format_score_capped};
+use crate::game_mode::{Difficulty, GameMode};
+use crate::high_scores::{HighScore, HighScores};
+use crate::key_holds::KeyHolds;
+use crate::level::{Campaign, Level};
+use crate::outline::Occupancy;
+use crate::play_style::TurnStats;
+use crate::prompt::{PromptResult, TextPrompt};
+use crate::quit::{QuitAction, QuitBehavior};
+use crate::replay::{FrameInput, Replay};
+use crate::screen::Screen;
+use crate::seed::{encode_seed, parse_seed};
+use crate::startup::StartupError;
+use crate::state_hash::StateHasher;
+use crate::summary::{EndCause, RunSummary};
+
+const TICK_RATE: u32 = 60;
+const HUD_SCORE_WIDTH: usize = 7;
+const VERSUS_PLAYERS: usize = 2;
+// Each player's score gets a column this wide at the top of the screen.
+const VERSUS_SCORE_WIDTH: i32 = 20;
+const PRACTICE_REWIND_TICKS: usize = 20;
+const TITLE_ROWS: usize = 5;
+const SETTINGS_ROWS: usize = 7;
+const SETTINGS_COLORS: [Color; 8] = [
+    Color::Green,
+    Color::Red,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::White,
+    Color::Grey,
+];
+const BACKGROUND_CHARACTERS: [char; 4] = ['.', ' ', ',', '`'];
+// Holding a menu key repeats it 15 times a second after 0.4 seconds.
+const MENU_REPEAT_DELAY: u64 = TICK_RATE as u64 * 2 / 5;
+const MENU_REPEAT_INTERVAL: u64 = TICK_RATE as u64 / 15;
+const SCORE_DECAY_INTERVAL: Duration = Duration::from_secs(15);
+const POWER_UP_MOVES: u32 = 40;
+const SHRINK_SEGMENTS: usize = 3;
+
+/// Plays the game in the terminal the way the `console_snek` binary does,
+/// with its options read from the command line.
+pub fn run() -> ExitCode {
+    logging::initialize_logging(LevelFilter::Info);
+
+    let options = match Options::parse(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(error) => return startup::present(&[error]),
+    };
+    let settings = match Config::load(config::default_path()) {
+        Ok(settings) => settings,
+        Err(error) => {
+            return startup::present(&[StartupError::InvalidConfig {
+                path: config::default_path().unwrap_or_default(),
+                error,
+            }])
+        }
+    };
+    let board = match settings.board() {
+        Ok(board) => board,
+        Err(error) => {
+            return startup::present(&[StartupError::InvalidConfig {
+                path: settings.path().cloned().unwrap_or_default(),
+                error: ConfigError::Board(error),
+            }])
+        }
+    };
+    let replay = match &options.replay {
+        Some(path) => match Replay::load(path) {
+            Ok(replay) => Some(replay),
+            Err(error) => {
+                return startup::present(&[StartupError::InvalidReplay {
+                    path: path.clone(),
+                    error,
+                }])
+            }
+        },
+        None => None,
+    };
+    let board = replay.as_ref().map_or(board, Replay::board);
+    let campaign =
+        level::default_dir().and_then(|dir| Campaign::new(level::load_levels(&dir, &board)));
+    let config = match &replay {
+        Some(replay) => {
+            let level_count = campaign.as_ref().map_or(0, Campaign::level_count);
+            if replay.level_count() > 0 && replay.level_count() != level_count {
+                warn!(
+                    "The replay was recorded with {} levels but {} were found, it may not play back the same",
+                    replay.level_count(),
+                    level_count
+                );
+            }
+            replay.config(campaign.clone())
+        }
+        None => RunConfig {
+            record_path: options.record.clone(),
+            ..RunConfig::from_settings(&settings, board)
+        },
+    };
+    let (width, height) = config.board.size();
+    let errors = startup::check_terminal(width.into(), height.into());
+    if !errors.is_empty() {
+        return startup::present(&errors);
+    }
+    if options.self_test {
+        return match self_test::run(&config.board) {
+            Ok(result) => {
+                self_test::report(&result, TICK_RATE);
+                ExitCode::SUCCESS
+            }
+            Err(error) => startup::present(&[StartupError::ConsoleInit(error)]),
+        };
+    }
+    let console = match ConsoleContext::new(width, height, TICK_RATE) {
+        Ok(console) => console,
+        Err(error) => return startup::present(&[StartupError::ConsoleInit(error)]),
+    };
+
+    let exit_status = ExitStatus::default();
+    let mut context = Context::new();
+    context.add(console).expect("failed to add ConsoleContext");
+    context
+        .add(exit_status.clone())
+        .expect("failed to add ExitStatus");
+    context
+        .add(HighScores::load(high_scores::default_path()))
+        .expect("failed to add HighScores");
+    context.add(settings).expect("failed to add Config");
+    if let Some(campaign) = campaign {
+        context.add(campaign).expect("failed to add Campaign");
+    }
+
+    let first_state: Box<dyn State> = match replay {
+        Some(replay) => Box::from(ReplayState::new(config, replay)),
+        None => Box::from(TitleState::new(config)),
+    };
+    EngineBuilder::new()
+        .with_scheduler(Box::from(SimpleScheduler))
+        .build(context)
+        .run(first_state);
+
+    exit_status.outcome().exit_code(options.exit_code_mode)
+}
+
+#[derive(Clone)]
+pub struct RunConfig {
+    pub board: Board,
+    pub seed: Option<u64>,
+    pub seeded: bool,
+    pub food_spawn_delay: u32,
+    pub mode: GameMode,
+    pub difficulty: Difficulty,
+    pub score_decay_interval: Option<Duration>,
+    /// Two players share the keyboard instead of one playing alone.
+    pub versus: bool,
+    /// The level being played, or `None` for the open board.
+    pub campaign: Option<Campaign>,
+    /// The percent chance that newly spawned food is a power-up.
+    pub special_food_chance: u32,
+    /// Where to save a replay of the run when it ends.
+    pub record_path: Option<PathBuf>,
+}
+
+impl Default for RunConfig {
+    fn default() -> Self {
+        Self {
+            board: Board::default(),
+            seed: None,
+            seeded: false,
+            food_spawn_delay: 3,
+            mode: GameMode::default(),
+            difficulty: Difficulty::default(),
+            score_decay_interval: None,
+            versus: false,
+            campaign: None,
+            special_food_chance: 0,
+            record_path: None,
+        }
+    }
+}
+
+impl RunConfig {
+    /// A run on `board` with the player's settings.
+    pub fn from_settings(settings: &Config, board: Board) -> Self {
+        Self {
+            board,
+            mode: settings.mode,
+            difficulty: settings.difficulty,
+            special_food_chance: settings.special_food_chance,
+            // Levels stay off until they're picked on the title screen.
+            ..Self::default()
+        }
+    }
+
+    /// The same run again from the first level, keeping the seed.
+    pub fn retry(&self) -> Self {
+        let mut config = self.clone();
+        config.campaign = config.campaign.as_ref().map(Campaign::first);
+        config
+    }
+
+    pub fn rematch(&self) -> Self {
+        let mut config = self.retry();
+        config.seed = None;
+        config.seeded = false;
+        config
+    }
+
+    /// The state that plays a run with this config.  Every way of starting a
+    /// run goes through here, `game`, or `practice`, so none of them can drop
+    /// a setting.
+    pub fn start(self) -> Box<dyn State> {
+        Box::from(self.game())
+    }
+
+    pub fn game(self) -> GameState {
+        GameState::new(self)
+    }
+
+    /// A practice run from `snapshot`, which never counts towards records.
+    pub fn practice(self, snapshot: Snapshot) -> Box<dyn State> {
+        Box::from(self.game().practicing_from(snapshot))
+    }
+
+    pub fn seeded(&self, seed: u64) -> Self {
+        let mut config = self.retry();
+        config.seed = Some(seed);
+        config.seeded = true;
+        config
+    }
+
+    /// The seed whose leaderboard the run goes on, or `None` for the main
+    /// one.  A seeded run can be played again and again until the food falls
+    /// just right, so it only competes with runs of the same seed.
+    pub fn leaderboard_seed(&self) -> Option<u64> {
+        self.seed.filter(|_| self.seeded)
+    }
+
+    pub fn level(&self) -> Option<&Level> {
+        self.campaign.as_ref().map(Campaign::level)
+    }
+
+    pub fn is_wall(&self, location: Vector2) -> bool {
+        self.mode.is_wall(&self.board, location)
+            || self.level().is_some_and(|level| level.is_wall(location))
+    }
+
+    pub fn allows_food(&self, location: Vector2) -> bool {
+        self.level().is_none_or(|level| level.allows_food(location))
+    }
+
+    pub fn spawn_location(&self) -> Vector2 {
+        self.level()
+            .and_then(Level::spawn)
+            .unwrap_or_else(|| self.mode.spawn_location())
+    }
+
+    /// How many snakes the run puts on the board.
+    pub fn player_count(&self) -> usize {
+        if self.versus {
+            VERSUS_PLAYERS
+        } else {
+            1
+        }
+    }
+
+    /// Where each player's snake starts, with player two in the corner
+    /// opposite player one.
+    pub fn spawn_locations(&self) -> Vec<Vector2> {
+        let first = self.spawn_location();
+        let second = self.mode.opposite_spawn_location(&self.board);
+        [first, second]
+            .into_iter()
+            .take(self.player_count())
+            .collect()
+    }
+}
+
+#[derive(Clone)]
+pub struct Snapshot {
+    rng: StdRng,
+    players: Vec<Player>,
+    ticks: u64,
+    frames: u64,
+    frames_until_move: u32,
+    food: Food,
+    level_points: u32,
+    power_up: Option<PowerUp>,
+}
+
+/// A snake on the board and the steering and score that go with it.
+#[derive(Clone)]
+pub struct Player {
+    snake: Snake,
+    steering: Vector2,
+    score: u32,
+    peak_score: u32,
+    turns: TurnStats,
+}
+
+impl Player {
+    fn new(spawn: Vector2, config: &RunConfig) -> Self {
+        Self {
+            snake: Snake::new(spawn.x, spawn.y, config.board, config.mode),
+            steering: Vector2::new(0, 0),
+            score: 0,
+            peak_score: 0,
+            turns: TurnStats::default(),
+        }
+    }
+}
+
+pub struct GameState {
+    config: RunConfig,
+    rng: StdRng,
+    /// One player on their own, or two in versus.
+    players: Vec<Player>,
+    ticks: u64,
+    frames: u64,
+    frames_until_move: u32,
+    food: Food,
+    /// Points scored since the current level started.
+    level_points: u32,
+    /// The power-up in effect.  It acts on the whole board, so in versus it
+    /// speeds up, slows down or doubles the points of both snakes alike.
+    power_up: Option<PowerUp>,
+    history: VecDeque<Snapshot>,
+    practice_start: Option<Snapshot>,
+    /// Every frame's input so far, when the run is being recorded.
+    recording: Option<Replay>,
+    replaying: bool,
+    outline_only: bool,
+    confirming_quit: bool,
+}
+
+impl State for GameState {
+    fn setup(&mut self, context: &mut Context) {
+        if self.practice_start.is_some() {
+            return;
+        }
+        get_exit_status(context).set(ExitOutcome::Quit);
+        self.move_food();
+        for player in self.players.iter_mut() {
+            player.snake.velocity = Vector2::new(0, 0);
+            player.steering = player.snake.velocity;
+        }
+    }
+
+    fn update(&mut self, context: &mut Context) -> OptionalTransition {
+        let first = get_config(context).keys;
+        let keys = [first, KeyBindings::second_player(&first)];
+        let console = get_console(context);
+        console.wait_for_frame();
+
+        if self.confirming_quit {
+            if console.is_interrupt_pressed() {
+                return Some(Transition::Quit);
+            }
+            if console.is_key_pressed(KeyCode::Char('y')) {
+                return self.end_run(EndCause::Quit);
+            }
+            if console.is_key_pressed(KeyCode::Char('n')) || console.is_key_pressed(KeyCode::Esc) {
+                self.confirming_quit = false;
+            }
+            return None;
+        }
+        match QuitBehavior::Confirm.action(console) {
+            Some(QuitAction::Confirm) => {
+                self.confirming_quit = true;
+                return None;
+            }
+            Some(QuitAction::Transition(transition)) => return Some(transition),
+            None => (),
+        }
+        if let Some(practice_start) = &self.practice_start {
+            if console.is_key_pressed(KeyCode::Char('r')) {
+                self.restore(practice_start.clone());
+                return None;
+            }
+        }
+
+        if console.is_key_pressed(KeyCode::Char('o')) {
+            self.outline_only = !self.outline_only;
+        }
+
+        let inputs: Vec<FrameInput> = keys
+            .iter()
+            .take(self.players.len())
+            .map(|keys| FrameInput::read(console, keys))
+            .collect();
+        if let Some(recording) = &mut self.recording {
+            recording.record(&inputs);
+        }
+        self.advance(&inputs)
+    }
+
+    fn render(&mut self, context: &mut Context) -> RenderResult {
+        let style = get_config(context).style;
+        let console = get_console(context);
+        self.draw(console, &style);
+        console.draw();
+    }
+}
+
+impl GameState {
+    pub fn new(mut config: RunConfig) -> Self {
+        let seed = *config.seed.get_or_insert_with(random);
+        // Levels are laid out for one snake, so versus plays the open board.
+        if config.versus {
+            config.campaign = None;
+        }
+        let players = config
+            .spawn_locations()
+            .into_iter()
+            .map(|spawn| Player::new(spawn, &config))
+            .collect();
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+            players,
+            recording: config.record_path.is_some().then(|| Replay::new(&config)),
+            config,
+            ticks: 0,
+            frames: 0,
+            frames_until_move: 0,
+            food: Food::new(0, 0),
+            level_points: 0,
+            power_up: None,
+            history: VecDeque::with_capacity(PRACTICE_REWIND_TICKS + 1),
+            practice_start: None,
+            replaying: false,
+            outline_only: false,
+            confirming_quit: false,
+        }
+    }
+
+    fn practicing_from(mut self, snapshot: Snapshot) -> Self {
+        self.restore(snapshot.clone());
+        self.practice_start = Some(snapshot);
+        self.recording = None;
+        self
+    }
+
+    // Plays one frame of the run, with one input for each player.  Live runs
+    // and replays both come through here with the frame's input, so a
+    // recording plays back exactly.
+    fn advance(&mut self, inputs: &[FrameInput]) -> OptionalTransition {
+        for (player, input) in self.players.iter_mut().zip(inputs) {
+            if let Some(steering) = steer(*input, player.snake.velocity) {
+                player.steering = steering;
+            }
+            if input.grow() {
+                player.snake.grow();
+            }
+        }
+
+        // Rendering runs every frame, but the snakes only move every few
+        // frames, and a little more often as the score climbs.
+        self.frames += 1;
+        self.decay_score();
+        self.frames_until_move = self.frames_until_move.saturating_sub(1);
+        if self.frames_until_move > 0 {
+            return None;
+        }
+        self.frames_until_move = self.move_interval();
+        self.step()
+    }
+
+    fn draw(&mut self, console: &mut ConsoleContext, style: &Style) {
+        console.fill(style.background.pixel());
+        if self.config.mode == GameMode::Walled {
+            draw_walls(console, &self.config.board, style.wall);
+        }
+        if let Some(level) = self.config.level() {
+            for wall in level.walls() {
+                console.set_pixel(wall.x, wall.y, style.wall.pixel());
+            }
+        }
+        let mut hud = String::new();
+        if self.config.versus {
+            for (index, player) in self.players.iter().enumerate() {
+                console.print_colored(
+                    VERSUS_SCORE_WIDTH * index as i32,
+                    0,
+                    format!(
+                        "P{}: {}",
+                        index + 1,
+                        format_score_capped(player.score.into(), HUD_SCORE_WIDTH)
+                    )
+                    .as_str(),
+                    player_style(style, index).snake_body.color,
+                );
+            }
+        } else {
+            let player = &self.players[0];
+            hud.push_str(
+                format!(
+                    "Score: {}",
+                    format_score_capped(player.score.into(), HUD_SCORE_WIDTH)
+                )
+                .as_str(),
+            );
+            if self.config.score_decay_interval.is_some() {
+                hud.push_str(
+                    format!(
+                        "  Peak: {}",
+                        format_score_capped(player.peak_score.into(), HUD_SCORE_WIDTH)
+                    )
+                    .as_str(),
+                );
+            }
+        }
+        if let Some(campaign) = &self.config.campaign {
+            hud.push_str(
+                format!("  Level {}: {}", campaign.number(), campaign.level().name).as_str(),
+            );
+        }
+        if let Some(power_up) = self.power_up {
+            hud.push_str(format!("  {} ({})", power_up.kind, power_up.moves_left).as_str());
+        }
+        if self.practice_start.is_some() {
+            hud.push_str("  PRACTICE (r to reset)");
+        } else if self.replaying {
+            hud.push_str("  REPLAY");
+        } else if self.config.seeded {
+            hud.push_str("  seeded game");
+        }
+        let hud_x = if self.config.versus {
+            VERSUS_SCORE_WIDTH * self.players.len() as i32
+        } else {
+            0
+        };
+        console.print(hud_x, 0, hud.as_str());
+        for (index, player) in self.players.iter_mut().enumerate() {
+            player
+                .snake
+                .draw(console, &player_style(style, index), self.outline_only);
+        }
+        self.food.draw(console, style);
+        if self.confirming_quit {
+            let prompt = if self.config.versus {
+                "Leave this match? (y / n)"
+            } else {
+                "Quit this run? (y / n)"
+            };
+            console.print(0, self.config.board.height() / 2, prompt);
+        }
+        #[cfg(debug_assertions)]
+        {
+            let mut diagnostics = format!("{} B/frame", console.frame_bytes());
+            if console.clipped_writes() > 0 {
+                diagnostics.push_str(&format!(" clipped: {}", console.clipped_writes()));
+            }
+            if console.skipped_presents() > 0 {
+                diagnostics.push_str(&format!(" skipped: {}", console.skipped_presents()));
+            }
+            let x = self.config.board.width() - diagnostics.len() as i32;
+            console.overlay(x, 0, diagnostics);
+        }
+    }
+
+    fn step(&mut self) -> OptionalTransition {
+        if self.practice_start.is_none() {
+            self.record_snapshot();
+        }
+
+        let crashed = self.crashed_players();
+        if crashed.contains(&true) {
+            return self.crash(&crashed, EndCause::SelfCollision);
+        }
+
+        let eater = self.players.iter().position(|player| {
+            self.food.is_active() && player.snake.location == self.food.location
+        });
+        if let Some(eater) = eater {
+            let kind = self.food.kind;
+            if self.practice_start.is_none() {
+                let points = match self.power_up {
+                    Some(PowerUp {
+                        kind: FoodKind::DoublePoints,
+                        ..
+                    }) => 2,
+                    _ => 1,
+                };
+                let player = &mut self.players[eater];
+                player.score += points;
+                player.peak_score = player.peak_score.max(player.score);
+                self.level_points += points;
+            }
+            let snake = &mut self.players[eater].snake;
+            match kind {
+                FoodKind::Normal => snake.grow(),
+                FoodKind::Shrink => snake.shrink(SHRINK_SEGMENTS),
+                kind => {
+                    snake.grow();
+                    self.power_up = Some(PowerUp {
+                        kind,
+                        moves_left: POWER_UP_MOVES,
+                    });
+                }
+            }
+            if self
+                .config
+                .level()
+                .is_some_and(|level| self.level_points >= level.goal)
+            {
+                if let Some(campaign) = self.config.campaign.as_ref().and_then(Campaign::next) {
+                    return self.start_level(campaign);
+                }
+            }
+            self.food.kind = self.roll_food_kind();
+            if !self.respawn_food() {
+                return self.end_run(EndCause::BoardFull);
+            }
+        }
+
+        let mut crashed = Vec::with_capacity(self.players.len());
+        for player in self.players.iter_mut() {
+            if player.steering != player.snake.velocity {
+                player
+                    .turns
+                    .record_turn(player.snake.velocity, player.steering);
+                player.snake.velocity = player.steering;
+            }
+            crashed.push(self.config.is_wall(player.snake.next_location()));
+        }
+        if crashed.contains(&true) {
+            return self.crash(&crashed, EndCause::WallCollision);
+        }
+
+        for player in self.players.iter_mut() {
+            player.snake.update();
+            player
+                .turns
+                .record_tick(&self.config.board, player.snake.location);
+        }
+        self.power_up = self.power_up.and_then(PowerUp::tick);
+        self.ticks += 1;
+        if !self.update_food() {
+            return self.end_run(EndCause::BoardFull);
+        }
+
+        None
+    }
+
+    // Which players' heads have run into a snake on the last move: their own
+    // body, or any part of someone else's, meeting head-on included.
+    fn crashed_players(&self) -> Vec<bool> {
+        self.players
+            .iter()
+            .enumerate()
+            .map(|(index, player)| {
+                let snake = &player.snake;
+                snake
+                    .body
+                    .iter()
+                    .any(|segment| segment.location == snake.location)
+                    || self
+                        .players
+                        .iter()
+                        .enumerate()
+                        .any(|(other, player)| other != index && snake.has_run_into(&player.snake))
+            })
+            .collect()
+    }
+
+    // Ends the round for the `crashed` players.  On your own that ends the
+    // run; in versus whoever is left standing wins, or it's a draw when
+    // everyone went down on the same move.
+    fn crash(&mut self, crashed: &[bool], end_cause: EndCause) -> OptionalTransition {
+        if !self.config.versus {
+            return self.end_run(end_cause);
+        }
+        let winner = only((0..crashed.len()).filter(|player| !crashed[*player]));
+        Some(self.match_over(winner))
+    }
+
+    fn leading_score(&self) -> u32 {
+        self.players
+            .iter()
+            .map(|player| player.score)
+            .max()
+            .unwrap_or_default()
+    }
+
+    fn move_interval(&self) -> u32 {
+        let difficulty = self.config.difficulty;
+        let score = self.leading_score();
+        match self.power_up.map(|power_up| power_up.kind) {
+            Some(FoodKind::SpeedUp) => difficulty.sped_up_interval(score),
+            Some(FoodKind::SlowDown) => difficulty.move_interval(score) * 2,
+            _ => difficulty.move_interval(score),
+        }
+    }
+
+    // Moves on to the next level with fresh snakes at its spawn points.  The
+    // scores carry over.
+    fn start_level(&mut self, campaign: Campaign) -> OptionalTransition {
+        debug!(
+            "Starting level {} ({}) at tick {}",
+            campaign.number(),
+            campaign.level().name,
+            self.ticks
+        );
+        self.config.campaign = Some(campaign);
+        for (player, spawn) in self.players.iter_mut().zip(self.config.spawn_locations()) {
+            player.snake = Snake::new(spawn.x, spawn.y, self.config.board, self.config.mode);
+            player.steering = player.snake.velocity;
+        }
+        self.level_points = 0;
+        self.power_up = None;
+        self.history.clear();
+        self.food.kind = FoodKind::Normal;
+        if !self.respawn_food() {
+            return self.end_run(EndCause::BoardFull);
+        }
+        None
+    }
+
+    fn roll_food_kind(&mut self) -> FoodKind {
+        let chance = self.config.special_food_chance;
+        if chance > 0 && self.rng.gen_bool(f64::from(chance) / 100.0) {
+            *FoodKind::SPECIAL
+                .choose(&mut self.rng)
+                .expect("there are special foods")
+        } else {
+            FoodKind::Normal
+        }
+    }
+
+    fn decay_score(&mut self) {
+        let Some(interval) = self.config.score_decay_interval else {
+            return;
+        };
+        if self.practice_start.is_some() {
+            return;
+        }
+        let interval_frames = (interval.as_secs_f64() * f64::from(TICK_RATE)).max(1.0) as u64;
+        if !self.frames.is_multiple_of(interval_frames) {
+            return;
+        }
+        for player in self.players.iter_mut().filter(|player| player.score > 0) {
+            player.score -= 1;
+            debug!(
+                "Score decayed to {} at frame {} (peak {})",
+                player.score, self.frames, player.peak_score
+            );
+        }
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            rng: self.rng.clone(),
+            players: self.players.clone(),
+            ticks: self.ticks,
+            frames: self.frames,
+            frames_until_move: self.frames_until_move,
+            food: self.food.clone(),
+            level_points: self.level_points,
+            power_up: self.power_up,
+        }
+    }
+
+    fn restore(&mut self, snapshot: Snapshot) {
+        self.rng = snapshot.rng;
+        self.players = snapshot.players;
+        self.ticks = snapshot.ticks;
+        self.frames = snapshot.frames;
+        self.frames_until_move = snapshot.frames_until_move;
+        self.food = snapshot.food;
+        self.level_points = snapshot.level_points;
+        self.power_up = snapshot.power_up;
+    }
+
+    fn record_snapshot(&mut self) {
+        if self.history.len() > PRACTICE_REWIND_TICKS {
+            self.history.pop_front();
+        }
+        self.history.push_back(self.snapshot());
+    }
+
+    fn end_run(&mut self, end_cause: EndCause) -> OptionalTransition {
+        match (&self.practice_start, end_cause) {
+            (Some(_), EndCause::Quit) => Some(Transition::Pop),
+            (Some(practice_start), _) => {
+                self.restore(practice_start.clone());
+                None
+            }
+            (None, EndCause::Quit) if self.config.versus => {
+                self.record_end();
+                Some(Transition::CleanPush(Box::from(TitleState::new(
+                    self.config.rematch(),
+                ))))
+            }
+            // Filling the board in versus goes to whoever ate the most.
+            (None, _) if self.config.versus => {
+                let top = self.leading_score();
+                let leader = only(
+                    (0..self.players.len()).filter(|player| self.players[*player].score == top),
+                );
+                Some(self.match_over(leader))
+            }
+            (None, _) => Some(self.game_over(end_cause)),
+        }
+    }
+
+    pub fn state_hash(&self) -> u64 {
+        let mut hasher = StateHasher::default();
+        hasher.write_u64(self.ticks);
+        hasher.write_u64(self.frames);
+        hasher.write_u32(self.frames_until_move);
+        hasher.write_i32(self.config.board.width());
+        hasher.write_i32(self.config.board.height());
+        hasher.write_u8(self.config.mode as u8);
+        hasher.write_u8(self.config.difficulty as u8);
+        hasher.write_u32(self.config.food_spawn_delay);
+        hasher.write_u64(
+            self.config
+                .score_decay_interval
+                .map_or(0, |interval| interval.as_millis() as u64),
+        );
+        hasher.write_u32(self.config.special_food_chance);
+        hasher.write_u64(
+            self.config
+                .campaign
+                .as_ref()
+                .map_or(0, |campaign| campaign.number() as u64),
+        );
+        hasher.write_u32(self.level_points);
+        match self.power_up {
+            Some(power_up) => {
+                hasher.write_u8(power_up.kind as u8);
+                hasher.write_u32(power_up.moves_left);
+            }
+            None => hasher.write_u8(0),
+        }
+        hasher.write_u64(self.players.len() as u64);
+        for player in self.players.iter() {
+            player.steering.hash_state(&mut hasher);
+            hasher.write_u32(player.score);
+            hasher.write_u32(player.peak_score);
+            player.snake.hash_state(&mut hasher);
+        }
+        self.food.hash_state(&mut hasher);
+        // Hashing the next draw captures the RNG's position in its stream
+        // without advancing it.
+        hasher.write_u64(self.rng.clone().next_u64());
+        hasher.finish()
+    }
+
+    // Logs how the run ended and saves its replay, when it's being recorded.
+    fn record_end(&self) {
+        debug!(
+            "Run ended at tick {} with state hash {:016x}",
+            self.ticks,
+            self.state_hash()
+        );
+        if let (Some(recording), Some(path)) = (&self.recording, &self.config.record_path) {
+            match recording.save(path, self.state_hash()) {
+                Ok(()) => info!("Saved a replay of the run to {}", path.display()),
+                Err(error) => warn!("Failed to save a replay to {}: {}", path.display(), error),
+            }
+        }
+    }
+
+    fn game_over(&self, end_cause: EndCause) -> Transition {
+        self.record_end();
+        let player = &self.players[0];
+        let summary = RunSummary::new(self.config.clone())
+            .with_score(player.score)
+            .with_peak_score(player.peak_score)
+            .with_length(player.snake.body.len() + 1)
+            .with_ticks(self.frames, TICK_RATE)
+            .with_turn_stats(player.turns.clone())
+            .with_end_cause(end_cause);
+        Transition::Push(Box::from(
+            GameOverState::new(summary).with_practice_start(self.history.front().cloned()),
+        ))
+    }
+
+    fn match_over(&self, winner: Option<usize>) -> Transition {
+        self.record_end();
+        let scores: Vec<u32> = self.players.iter().map(|player| player.score).collect();
+        debug!(
+            "Versus match ended with scores {:?}, winner {:?}",
+            scores, winner
+        );
+        Transition::Push(Box::from(VersusOverState {
+            config: self.config.clone(),
+            scores,
+            winner,
+        }))
+    }
+
+    fn respawn_food(&mut self) -> bool {
+        self.food.start_spawning(self.config.food_spawn_delay);
+        self.move_food()
+    }
+
+    fn update_food(&mut self) -> bool {
+        if !self.food.is_active()
+            && self
+                .players
+                .iter()
+                .any(|player| player.snake.occupancy.contains(self.food.location))
+        {
+            return self.respawn_food();
+        }
+        self.food.tick();
+        true
+    }
+
+    fn move_food(&mut self) -> bool {
+        let snakes: Vec<&Snake> = self.players.iter().map(|player| &player.snake).collect();
+        let location = find_food_location(&self.config, &snakes, &mut self.rng);
+        match location {
+            Some(location) => {
+                self.food.location = location;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+// The one index left in `indices`, or `None` when there are none or several.
+fn only(mut indices: impl Iterator<Item = usize>) -> Option<usize> {
+    let first = indices.next();
+    first.filter(|_| indices.next().is_none())
+}
+
+fn steer(input: FrameInput, velocity: Vector2) -> Option<Vector2> {
+    if input.is_pressed(Direction::Up) && velocity.y != 1 {
+        Some(Vector2::new(0, -1))
+    } else if input.is_pressed(Direction::Down) && velocity.y != -1 {
+        Some(Vector2::new(0, 1))
+    } else if input.is_pressed(Direction::Left) && velocity.x != 1 {
+        Some(Vector2::new(-1, 0))
+    } else if input.is_pressed(Direction::Right) && velocity.x != -1 {
+        Some(Vector2::new(1, 0))
+    } else {
+        None
+    }
+}
+
+// Picks a free cell for food, preferring cells at least one snake can reach.
+fn find_food_location(config: &RunConfig, snakes: &[&Snake], rng: &mut StdRng) -> Option<Vector2> {
+    let board = &config.board;
+    let blocked = |location: Vector2| {
+        config.is_wall(location)
+            || snakes
+                .iter()
+                .any(|snake| snake.occupancy.contains(location))
+    };
+    let free: Vec<Vector2> = (1..board.height())
+        .flat_map(|y| (1..board.width()).map(move |x| Vector2::new(x, y)))
+        .filter(|location| !blocked(*location) && config.allows_food(*location))
+        .collect();
+    if free.is_empty() {
+        debug!("No free cells are left for food, the board is full");
+        return None;
+    }
+
+    let reachable: HashSet<Vector2> = snakes
+        .iter()
+        .flat_map(|snake| reachable_locations(board, snake.location, &blocked))
+        .collect();
+    let candidates: Vec<Vector2> = free
+        .iter()
+        .copied()
+        .filter(|location| reachable.contains(location))
+        .collect();
+    if candidates.is_empty() {
+        debug!("No free cell is reachable from the head, spawning food in an unreachable cell");
+        return free.choose(rng).copied();
+    }
+    candidates.choose(rng).copied()
+}
+
+fn draw_walls(console: &mut ConsoleContext, board: &Board, wall: Glyph) {
+    for y in 0..board.height() {
+        for x in 0..board.width() {
+            if board.edge_distance(Vector2::new(x, y)) == 0 {
+                console.set_pixel(x, y, wall.pixel());
+            }
+        }
+    }
+}
+
+fn reachable_locations(
+    board: &Board,
+    start: Vector2,
+    blocked: &impl Fn(Vector2) -> bool,
+) -> HashSet<Vector2> {
+    let mut reachable = HashSet::from([start]);
+    let mut queue = VecDeque::from([start]);
+    while let Some(location) = queue.pop_front() {
+        for neighbor in board.neighbors(location) {
+            if !blocked(neighbor) && reachable.insert(neighbor) {
+                queue.push_back(neighbor);
+            }
+        }
+    }
+    reachable
+}
+
+/// Plays a recorded run back frame by frame, then checks that it ended in
+/// the same state as the recording.
+pub struct ReplayState {
+    game: GameState,
+    replay: Replay,
+    next_frame: usize,
+    /// The replayed run's state hash, once the recording runs out or the run
+    /// ends.
+    final_hash: Option<u64>,
+}
+
+impl State for ReplayState {
+    fn setup(&mut self, context: &mut Context) {
+        self.game.setup(context);
+    }
+
+    fn update(&mut self, context: &mut Context) -> OptionalTransition {
+        let console = get_console(context);
+        console.wait_for_frame();
+
+        if console.is_key_pressed(KeyCode::Esc) {
+            return Some(Transition::Quit);
+        }
+        if let Some(QuitAction::Transition(transition)) = QuitBehavior::Exit.action(console) {
+            return Some(transition);
+        }
+        if console.is_key_pressed(KeyCode::Char('o')) {
+            self.game.outline_only = !self.game.outline_only;
+        }
+        if self.final_hash.is_some() {
+            return None;
+        }
+
+        let ended = match self.replay.frame(self.next_frame) {
+            Some(inputs) => {
+                self.next_frame += 1;
+                self.game.advance(inputs).is_some()
+            }
+            None => true,
+        };
+        if ended {
+            self.finish();
+        }
+        None
+    }
+
+    fn render(&mut self, context: &mut Context) -> RenderResult {
+        let style = get_config(context).style;
+        let console = get_console(context);
+        self.game.draw(console, &style);
+        if let Some(final_hash) = self.final_hash {
+            let result = match self.replay.state_hash() {
+                Some(recorded_hash) if recorded_hash == final_hash => {
+                    "it matches the recording".to_string()
+                }
+                Some(recorded_hash) => format!(
+                    "it was {:016x} when recorded, this run is {:016x}",
+                    recorded_hash, final_hash
+                ),
+                None => "the recording has no state hash to check".to_string(),
+            };
+            let y = self.game.config.board.height() / 2;
+            console.print(
+                0,
+                y,
+                format!(
+                    "Replay finished after {} frames, {}",
+                    self.next_frame, result
+                )
+                .as_str(),
+            );
+            console.print(0, y + 1, "Press q or Esc to exit");
+        }
+        console.draw();
+    }
+}
+
+impl ReplayState {
+    pub fn new(config: RunConfig, replay: Replay) -> Self {
+        let mut game = config.game();
+        game.replaying = true;
+        Self {
+            game,
+            replay,
+            next_frame: 0,
+            final_hash: None,
+        }
+    }
+
+    fn finish(&mut self) {
+        let final_hash = self.game.state_hash();
+        match self.replay.state_hash() {
+            Some(recorded_hash) if recorded_hash != final_hash => warn!(
+                "Replay desynced: recorded state hash {:016x}, replayed {:016x}",
+                recorded_hash, final_hash
+            ),
+            _ => info!("Replay finished with state hash {:016x}", final_hash),
+        }
+        self.final_hash = Some(final_hash);
+    }
+}
+
+// Player two's snake keeps the configured glyphs but takes a color that
+// stands apart from player one's.
+fn player_two_style(style: &Style) -> Style {
+    let (bright, dark) = if style.snake_body.color == Color::Cyan {
+        (Color::Magenta, Color::DarkMagenta)
+    } else {
+        (Color::Cyan, Color::DarkCyan)
+    };
+    Style {
+        snake_head: Glyph::new(style.snake_head.character, dark),
+        snake_body: Glyph::new(style.snake_body.character, bright),
+        snake_tail: Glyph::new(style.snake_tail.character, dark),
+        snake_interior: Glyph::new(style.snake_interior.character, dark),
+        ..*style
+    }
+}
+
+fn player_style(style: &Style, player: usize) -> Style {
+    if player == 0 {
+        *style
+    } else {
+        player_two_style(style)
+    }
+}
+
+pub struct VersusOverState {
+    config: RunConfig,
+    scores: Vec<u32>,
+    /// The player who survived, or `None` for a draw.
+    winner: Option<usize>,
+}
+
+impl State for VersusOverState {
+    fn update(&mut self, context: &mut Context) -> OptionalTransition {
+        let console = get_console(context);
+
+        if console.is_key_pressed(KeyCode::Char('y')) {
+            return Some(Transition::CleanPush(self.config.rematch().start()));
+        }
+        if console.is_key_pressed(KeyCode::Char('m')) {
+            return Some(Transition::CleanPush(Box::from(TitleState::new(
+                self.config.rematch(),
+            ))));
+        }
+        if console.is_key_pressed(KeyCode::Char('n')) {
+            return Some(Transition::Quit);
+        }
+        if let Some(QuitAction::Transition(transition)) = QuitBehavior::Exit.action(console) {
+            return Some(transition);
+        }
+
+        None
+    }
+
+    fn render(&mut self, context: &mut Context) -> RenderResult {
+        let console = get_console(context);
+        console.wait_for_frame();
+        let headline = match self.winner {
+            Some(player) => format!("Player {} survived!", player + 1),
+            None => "Both snakes are out, it's a draw.".to_string(),
+        };
+        console.print(0, 0, headline.as_str());
+        let scores: Vec<String> = self
+            .scores
+            .iter()
+            .enumerate()
+            .map(|(player, score)| {
+                format!(
+                    "Player {}: {} points",
+                    player + 1,
+                    format_score((*score).into())
+                )
+            })
+            .collect();
+        console.print(0, 1, scores.join("  ").as_str());
+        console.print(0, 3, "Play again? (y / n, m for the menu)");
+        console.draw();
+    }
+}
+
+#[derive(Clone)]
+pub struct GameOverState {
+    summary: RunSummary,
+    practice_start: Option<Snapshot>,
+    entry_offered: bool,
+    highlight: Option<usize>,
+}
+
+impl State for GameOverState {
+    fn setup(&mut self, context: &mut Context) {
+        get_exit_status(context).set(self.summary.end_cause.into());
+    }
+
+    fn update(&mut self, context: &mut Context) -> OptionalTransition {
+        if !self.entry_offered {
+            self.entry_offered = true;
+            if get_high_scores(context).qualifies(
+                self.summary.config.leaderboard_seed(),
+                self.summary.peak_score,
+            ) {
+                return Some(Transition::Push(Box::from(HighScoreEntryState::new(
+                    self.clone(),
+                ))));
+            }
+        }
+        let console = get_console(context);
+
+        if console.is_key_pressed(KeyCode::Char('y')) {
+            return Some(Transition::CleanPush(self.summary.config.rematch().start()));
+        }
+        if console.is_key_pressed(KeyCode::Char('r')) {
+            return Some(Transition::CleanPush(self.summary.config.retry().start()));
+        }
+        if let Some(practice_start) = &self.practice_start {
+            if console.is_key_pressed(KeyCode::Char('p')) {
+                return Some(Transition::Push(
+                    self.summary.config.clone().practice(practice_start.clone()),
+                ));
+            }
+        }
+        if console.is_key_pressed(KeyCode::Char('s')) {
+            return Some(Transition::Push(Box::from(SeedEntryState::new(
+                self.summary.config.rematch(),
+            ))));
+        }
+        if console.is_key_pressed(KeyCode::Char('m')) {
+            return Some(Transition::CleanPush(Box::from(TitleState::new(
+                self.summary.config.rematch(),
+            ))));
+        }
+        if console.is_key_pressed(KeyCode::Char('l')) {
+            return Some(Transition::Push(Box::from(LeaderboardState::new(
+                self.summary.config.leaderboard_seed(),
+                self.highlight,
+            ))));
+        }
+        if console.is_key_pressed(KeyCode::Char('n')) {
+            return Some(Transition::Quit);
+        }
+        if let Some(QuitAction::Transition(transition)) = QuitBehavior::Exit.action(console) {
+            return Some(transition);
+        }
+
+        None
+    }
+
+    fn render(&mut self, context: &mut Context) -> RenderResult {
+        let console = get_console(context);
+        console.wait_for_frame();
+        let mut headline = format!(
+            "{} You got {} points!",
+            self.summary.headline(),
+            format_score(self.summary.score.into())
+        );
+        if self.summary.config.score_decay_interval.is_some() {
+            headline.push_str(
+                format!(" Peak: {}", format_score(self.summary.peak_score.into())).as_str(),
+            );
+        }
+        console.print(0, 0, headline.as_str());
+        let mut stats = format!(
+            "Length: {}  Time: {}",
+            self.summary.length,
+            format_duration(self.summary.duration())
+        );
+        if let Some(campaign) = &self.summary.config.campaign {
+            stats.push_str(
+                format!(
+                    "  Level: {} of {}",
+                    campaign.number(),
+                    campaign.level_count()
+                )
+                .as_str(),
+            );
+        }
+        console.print(0, 1, stats.as_str());
+        console.print(
+            0,
+            2,
+            format!(
+                "Play style: {} ({} turns)",
+                self.summary.turns.play_style(),
+                self.summary.turns.turns()
+            )
+            .as_str(),
+        );
+        if let Some(seed) = self.summary.config.seed {
+            console.print(0, 3, format!("Seed: {}", encode_seed(seed)).as_str());
+        }
+        console.print(0, 4, "Play again? (y / n, r to retry the same seed)");
+        console.print(
+            0,
+            5,
+            "Press s to play a specific seed, m for the menu, l for the leaderboard",
+        );
+        if self.practice_start.is_some() {
+            console.print(
+                0,
+                6,
+                format!(
+                    "Press p to practice from {} ticks earlier",
+                    PRACTICE_REWIND_TICKS
+                )
+                .as_str(),
+            );
+        }
+        let high_scores = get_high_scores(context);
+        let seed = self.summary.config.leaderboard_seed();
+        let leaderboard = match seed {
+            Some(seed) => format!("leaderboard for seed {}", encode_seed(seed)),
+            None => "leaderboard".to_string(),
+        };
+        let ranking = match self.highlight {
+            Some(rank) => Some(format!(
+                "Your run ranked #{} on the {}!",
+                rank + 1,
+                leaderboard
+            )),
+            None if !high_scores.qualifies(seed, self.summary.peak_score) => Some(format!(
+                "Your run didn't make the top {} on the {}.",
+                high_scores::MAX_ENTRIES,
+                leaderboard
+            )),
+            None => None,
+        };
+        let entries: Vec<String> = high_scores
+            .entries(seed)
+            .iter()
+            .enumerate()
+            .map(|(rank, entry)| {
+                format!(
+                    "{:>2}. {:<width$} {:>10}",
+                    rank + 1,
+                    entry.name,
+                    format_score(entry.score.into()),
+                    width = high_scores::NAME_LENGTH
+                )
+            })
+            .collect();
+        let console = get_console(context);
+        if let Some(ranking) = ranking {
+            console.print_colored(0, 7, ranking.as_str(), Color::Yellow);
+        }
+        if !entries.is_empty() {
+            console.print(0, 8, "High Scores");
+        }
+        for (rank, line) in entries.iter().enumerate() {
+            let y = 9 + rank as i32;
+            if self.highlight == Some(rank) {
+                console.print_colored(0, y, line, Color::Yellow);
+            } else {
+                console.print(0, y, line);
+            }
+        }
+        console.draw();
+    }
+}
+
+impl GameOverState {
+    pub fn new(summary: RunSummary) -> Self {
+        Self {
+            summary,
+            practice_start: None,
+            entry_offered: false,
+            highlight: None,
+        }
+    }
+
+    pub fn with_practice_start(mut self, practice_start: Option<Snapshot>) -> Self {
+        self.practice_start = practice_start;
+        self
+    }
+
+    pub fn with_highlight(mut self, rank: Option<usize>) -> Self {
+        self.highlight = rank;
+        self
+    }
+}
+
+pub struct HighScoreEntryState {
+    game_over: GameOverState,
+    prompt: TextPrompt,
+}
+
+impl State for HighScoreEntryState {
+    fn update(&mut self, context: &mut Context) -> OptionalTransition {
+        let console = get_console(context);
+
+        if console.is_interrupt_pressed() {
+            return Some(Transition::Quit);
+        }
+        match self.prompt.update(console) {
+            PromptResult::Confirmed(name) => {
+                let high_scores = get_high_scores(context);
+                let name = name.to_ascii_uppercase();
+                let summary = &self.game_over.summary;
+                let rank = high_scores.insert(
+                    summary.config.leaderboard_seed(),
+                    HighScore::new(&name, summary.peak_score, summary.length),
+                );
+                if rank.is_some() {
+                    if let Err(error) = high_scores.save() {
+                        warn!("Failed to save high scores: {}", error);
+                    }
+                }
+                Some(Transition::CleanPush(Box::from(
+                    self.game_over.clone().with_highlight(rank),
+                )))
+            }
+            PromptResult::Cancelled => Some(Transition::Pop),
+            PromptResult::Pending => None,
+        }
+    }
+
+    fn render(&mut self, context: &mut Context) -> RenderResult {
+        let console = get_console(context);
+        console.wait_for_frame();
+        console.fill(pixel::pxl(' '));
+        console.print(
+            0,
+            0,
+            format!(
+                "New high score: {} points!",
+                format_score(self.game_over.summary.peak_score.into())
+            )
+            .as_str(),
+        );
+        self.prompt.draw(console, 0, 2);
+        console.print(0, 5, "Enter to save, Esc to skip");
+        console.draw();
+    }
+}
+
+pub struct LeaderboardState {
+    /// The seed whose leaderboard is shown, or `None` for the main one.
+    seed: Option<u64>,
+    highlight: Option<usize>,
+}
+
+impl State for LeaderboardState {
+    fn update(&mut self, context: &mut Context) -> OptionalTransition {
+        let console = get_console(context);
+
+        if console.is_key_pressed(KeyCode::Esc) {
+            return Some(Transition::Pop);
+        }
+        if let Some(QuitAction::Transition(transition)) = QuitBehavior::Back.action(console) {
+            return Some(transition);
+        }
+
+        None
+    }
+
+    fn render(&mut self, context: &mut Context) -> RenderResult {
+        let high_scores = get_high_scores(context);
+        let rows: Vec<String> = high_scores
+            .entries(self.seed)
+            .iter()
+            .enumerate()
+            .map(|(rank, entry)| {
+                format!(
+                    "{:>4}  {:<4}  {:>10}  {:>6}  {}",
+                    format!("{}.", rank + 1),
+                    entry.name,
+                    format_score(entry.score.into()),
+                    match entry.length {
+                        0 => "-".to_string(),
+                        length => length.to_string(),
+                    },
+                    match entry.recorded_at {
+                        0 => "-".to_string(),
+                        recorded_at => format_date(recorded_at),
+                    }
+                )
+            })
+            .collect();
+        let console = get_console(context);
+        console.wait_for_frame();
+        console.fill(pixel::pxl(' '));
+        match self.seed {
+            Some(seed) => console.print(
+                0,
+                0,
+                format!("Leaderboard for seed {}", encode_seed(seed)).as_str(),
+            ),
+            None => console.print(0, 0, "Leaderboard"),
+        }
+        if rows.is_empty() {
+            console.print(0, 2, "No high scores yet.");
+        } else {
+            console.print(0, 2, "Rank  Name       Score  Length  Date");
+        }
+        for (rank, row) in rows.iter().enumerate() {
+            let y = 3 + rank as i32;
+            if self.highlight == Some(rank) {
+                console.print_colored(0, y, row, Color::Yellow);
+            } else {
+                console.print(0, y, row);
+            }
+        }
+        console.print(
+            0,
+            4 + high_scores::MAX_ENTRIES as i32,
+            "Press Esc or q to go back",
+        );
+        console.draw();
+    }
+}
+
+impl LeaderboardState {
+    pub fn new(seed: Option<u64>, highlight: Option<usize>) -> Self {
+        Self { seed, highlight }
+    }
+}
+
+impl HighScoreEntryState {
+    pub fn new(game_over: GameOverState) -> Self {
+        Self {
+            game_over,
+            prompt: TextPrompt::new("Initials", high_scores::NAME_LENGTH)
+                .with_validator(high_scores::validate_name),
+        }
+    }
+}
+
+pub struct SeedEntryState {
+    config: RunConfig,
+    prompt: TextPrompt,
+}
+
+impl State for SeedEntryState {
+    fn update(&mut self, context: &mut Context) -> OptionalTransition {
+        let console = get_console(context);
+
+        if console.is_interrupt_pressed() {
+            return Some(Transition::Quit);
+        }
+        match self.prompt.update(console) {
+            PromptResult::Confirmed(text) => {
+                let seed = parse_seed(&text).expect("the prompt only confirms valid seeds");
+                Some(Transition::CleanPush(self.config.seeded(seed).start()))
+            }
+            PromptResult::Cancelled => Some(Transition::Pop),
+            PromptResult::Pending => None,
+        }
+    }
+
+    fn render(&mut self, context: &mut Context) -> RenderResult {
+        let console = get_console(context);
+        console.wait_for_frame();
+        console.fill(pixel::pxl(' '));
+        console.print(0, 0, "Play a seed: decimal, 0x hex, or a shared #seed");
+        self.prompt.draw(console, 0, 2);
+        console.print(0, 5, "Enter to play, Esc to go back");
+        console.draw();
+    }
+}
+
+impl SeedEntryState {
+    pub fn new(config: RunConfig) -> Self {
+        Self {
+            config,
+            prompt: TextPrompt::new("Seed", 20).with_validator(|text| parse_seed(text).map(|_| ())),
+        }
+    }
+}
+
+pub struct TitleState {
+    config: RunConfig,
+    selected_row: usize,
+}
+
+impl State for TitleState {
+    fn setup(&mut self, context: &mut Context) {
+        // Leaving from the menu is a quit, however the last run ended.
+        get_exit_status(context).set(ExitOutcome::Quit);
+    }
+
+    fn update(&mut self, context: &mut Context) -> OptionalTransition {
+        let campaign = context.get::<Campaign>().cloned();
+        let console = get_console(context);
+
+        if console.is_key_pressed(KeyCode::Enter) {
+            return Some(Transition::CleanPush(self.config.clone().start()));
+        }
+        if console.is_key_pressed(KeyCode::Char('s')) {
+            return Some(Transition::Push(Box::from(SeedEntryState::new(
+                self.config.clone(),
+            ))));
+        }
+        if console.is_key_pressed(KeyCode::Char('c')) {
+            return Some(Transition::Push(Box::from(SettingsState::default())));
+        }
+        if console.is_key_repeating(KeyCode::Up) {
+            self.selected_row = self.selected_row.saturating_sub(1);
+        }
+        if console.is_key_repeating(KeyCode::Down) {
+            self.selected_row = (self.selected_row + 1).min(TITLE_ROWS - 1);
+        }
+        let offset = if console.is_key_repeating(KeyCode::Left) {
+            -1
+        } else if console.is_key_repeating(KeyCode::Right) {
+            1
+        } else {
+            0
+        };
+        if offset != 0 {
+            match self.selected_row {
+                0 => self.config.mode = cycle(&GameMode::ALL, self.config.mode, offset),
+                1 => {
+                    self.config.difficulty = cycle(&Difficulty::ALL, self.config.difficulty, offset)
+                }
+                2 => {
+                    self.config.score_decay_interval = match self.config.score_decay_interval {
+                        Some(_) => None,
+                        None => Some(SCORE_DECAY_INTERVAL),
+                    }
+                }
+                3 => self.config.versus = !self.config.versus,
+                _ => {
+                    self.config.campaign = match self.config.campaign {
+                        Some(_) => None,
+                        None => campaign,
+                    }
+                }
+            }
+        }
+        if let Some(QuitAction::Transition(transition)) = QuitBehavior::Exit.action(console) {
+            return Some(transition);
+        }
+
+        None
+    }
+
+    fn render(&mut self, context: &mut Context) -> RenderResult {
+        let level_count = context.get::<Campaign>().map(Campaign::level_count);
+        let keys = get_config(context).keys;
+        let console = get_console(context);
+        console.wait_for_frame();
+        console.fill(pixel::pxl(' '));
+        console.print(0, 0, "Console Snek");
+        let rows = [
+            format!("Mode:        < {} >", self.config.mode),
+            format!("Difficulty:  < {} >", self.config.difficulty),
+            format!(
+                "Score decay: < {} >",
+                match self.config.score_decay_interval {
+                    Some(interval) => format!("-1 every {}s", interval.as_secs()),
+                    None => "Off".to_string(),
+                }
+            ),
+            format!(
+                "Players:     < {} >",
+                if self.config.versus {
+                    format!(
+                        "2 (versus, {} vs {})",
+                        KeyBindings::second_player(&keys).label(),
+                        keys.label()
+                    )
+                } else {
+                    "1".to_string()
+                }
+            ),
+            format!(
+                "Levels:      < {} >",
+                match (level_count, &self.config.campaign) {
+                    (None, _) => "None found".to_string(),
+                    (Some(_), None) => "Off".to_string(),
+                    (Some(count), Some(_)) if self.config.versus => {
+                        format!("On ({} levels, 1 player only)", count)
+                    }
+                    (Some(count), Some(_)) => format!("On ({} levels)", count),
+                }
+            ),
+        ];
+        for (index, row) in rows.iter().enumerate() {
+            let y = 2 + index as i32;
+            if index == self.selected_row {
+                console.print_colored(0, y, format!("> {}", row).as_str(), Color::Yellow);
+            } else {
+                console.print(0, y, format!("  {}", row).as_str());
+            }
+        }
+        console.print(0, 7, "Up / Down to pick, Left / Right to change");
+        console.print(
+            0,
+            8,
+            "Enter to start, s to play a specific seed, c for settings, q to quit",
+        );
+        console.draw();
+    }
+}
+
+impl TitleState {
+    pub fn new(config: RunConfig) -> Self {
+        Self {
+            config,
+            selected_row: 0,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct SettingsState {
+    selected_row: usize,
+    /// Why the last change was refused, or why the settings can't be saved.
+    error: Option<String>,
+}
+
+impl State for SettingsState {
+    fn update(&mut self, context: &mut Context) -> OptionalTransition {
+        let console = get_console(context);
+
+        let leave = if console.is_key_pressed(KeyCode::Esc) {
+            Some(Transition::Pop)
+        } else {
+            match QuitBehavior::Back.action(console) {
+                Some(QuitAction::Transition(transition)) => Some(transition),
+                _ => None,
+            }
+        };
+        if console.is_key_repeating(KeyCode::Up) {
+            self.selected_row = self.selected_row.saturating_sub(1);
+        }
+        if console.is_key_repeating(KeyCode::Down) {
+            self.selected_row = (self.selected_row + 1).min(SETTINGS_ROWS - 1);
+        }
+        let offset = if console.is_key_repeating(KeyCode::Left) {
+            -1
+        } else if console.is_key_repeating(KeyCode::Right) {
+            1
+        } else {
+            0
+        };
+
+        let settings = get_config_mut(context);
+        if offset != 0 {
+            self.change(settings, offset);
+        }
+        let leave = leave?;
+        match settings.check_board(term_size::dimensions()) {
+            Ok(()) => {
+                if let Err(error) = settings.save() {
+                    warn!("Failed to save settings: {}", error);
+                }
+            }
+            // Ctrl+C still exits, just without saving.
+            Err(error) if matches!(leave, Transition::Quit) => {
+                warn!("Not saving settings: {}", error);
+            }
+            Err(error) => {
+                self.error = Some(error);
+                return None;
+            }
+        }
+        Some(leave)
+    }
+
+    fn render(&mut self, context: &mut Context) -> RenderResult {
+        let settings = get_config(context);
+        let on_off = |enabled: bool| if enabled { "On" } else { "Off" };
+        let rows = [
+            format!("Board width:     < {} >", settings.board.width),
+            format!("Board height:    < {} >", settings.board.height),
+            format!(
+                "Fit to terminal: < {} >",
+                on_off(settings.board.fit_to_terminal)
+            ),
+            format!("Vim keys (hjkl): < {} >", on_off(settings.keys.vim_keys)),
+            format!(
+                "Snake color:     < {} >",
+                config::color_name(settings.style.snake_body.color)
+            ),
+            format!(
+                "Food color:      < {} >",
+                config::color_name(settings.style.food.color)
+            ),
+            format!(
+                "Background:      < '{}' >",
+                settings.style.background.character
+            ),
+        ];
+        let saved_to = settings
+            .path()
+            .map(|path| format!("Saved to {}", path.display()));
+        let console = get_console(context);
+        console.wait_for_frame();
+        console.fill(pixel::pxl(' '));
+        console.print(0, 0, "Settings");
+        for (index, row) in rows.iter().enumerate() {
+            let y = 2 + index as i32;
+            if index == self.selected_row {
+                console.print_colored(0, y, format!("> {}", row).as_str(), Color::Yellow);
+            } else {
+                console.print(0, y, format!("  {}", row).as_str());
+            }
+        }
+        let footer = 3 + SETTINGS_ROWS as i32;
+        console.print(
+            0,
+            footer,
+            "Board changes apply the next time the game starts",
+        );
+        console.print(0, footer + 1, "Up / Down to pick, Left / Right to change");
+        console.print(0, footer + 2, "Esc or q to save and go back");
+        if let Some(error) = &self.error {
+            console.print_colored(0, footer + 3, error.as_str(), Color::Red);
+        }
+        if let Some(saved_to) = saved_to {
+            console.print(0, footer + 4, saved_to.as_str());
+        }
+        console.draw();
+    }
+}
+
+impl SettingsState {
+    fn change(&mut self, settings: &mut Config, offset: isize) {
+        self.error = None;
+        let step_dimension = |dimension: u16, min: u16| {
+            dimension
+                .saturating_add_signed(offset as i16)
+                .clamp(min, board::MAX_BOARD_DIMENSION)
+        };
+        match self.selected_row {
+            0 => {
+                settings.board.width =
+                    step_dimension(settings.board.width, config::MIN_PLAYABLE_WIDTH)
+            }
+            1 => {
+                settings.board.height =
+                    step_dimension(settings.board.height, config::MIN_PLAYABLE_HEIGHT)
+            }
+            2 => settings.board.fit_to_terminal = !settings.board.fit_to_terminal,
+            3 => {
+                let keys = KeyBindings {
+                    vim_keys: !settings.keys.vim_keys,
+                    ..settings.keys
+                };
+                match keys.check() {
+                    Ok(()) => settings.keys = keys,
+                    Err(clash) => self.error = Some(format!("Vim keys would clash: {}", clash)),
+                }
+            }
+            4 => {
+                let color = &mut settings.style.snake_body.color;
+                *color = cycle(&SETTINGS_COLORS, *color, offset);
+            }
+            5 => {
+                let color = &mut settings.style.food.color;
+                *color = cycle(&SETTINGS_COLORS, *color, offset);
+            }
+            _ => {
+                let character = &mut settings.style.background.character;
+                *character = cycle(&BACKGROUND_CHARACTERS, *character, offset);
+            }
+        }
+    }
+}
+
+fn cycle<T: Copy + PartialEq>(options: &[T], current: T, offset: isize) -> T {
+    let index = options
+        .iter()
+        .position(|option| *option == current)
+        .unwrap_or(0);
+    let next = (index as isize + offset).rem_euclid(options.len() as isize);
+    options[next as usize]
+}
+
+fn get_console(context: &mut Context) -> &mut ConsoleContext {
+    context
+        .get_mut::<ConsoleContext>()
+        .expect("no ConsoleContext")
+}
+
+fn get_exit_status(context: &Context) -> &ExitStatus {
+    context.get::<ExitStatus>().expect("no ExitStatus")
+}
+
+fn get_high_scores(context: &mut Context) -> &mut HighScores {
+    context.get_mut::<HighScores>().expect("no HighScores")
+}
+
+fn get_config(context: &Context) -> &Config {
+    context.get::<Config>().expect("no Config")
+}
+
+fn get_config_mut(context: &mut Context) -> &mut Config {
+    context.get_mut::<Config>().expect("no Config")
+}
+
+#[derive(Clone)]
+pub struct Snake {
+    board: Board,
+    mode: GameMode,
+    pub location: Vector2,
+    pub previous_location: Option<Vector2>,
+    pub velocity: Vector2,
+    pub body: VecDeque<BodySegment>,
+    pub occupancy: Occupancy,
+}
+
+impl Snake {
+    pub fn new(x: i32, y: i32, board: Board, mode: GameMode) -> Self {
+        let location = Vector2::new(x, y);
+        let mut occupancy = Occupancy::new(board);
+        occupancy.insert(location);
+        Self {
+            board,
+            mode,
+            location,
+            previous_location: None,
+            velocity: Vector2::new(0, 0),
+            body: VecDeque::new(),
+            occupancy,
+        }
+    }
+
+    pub fn update(&mut self) {
+        if self.velocity.x != 0 || self.velocity.y != 0 {
+            self.previous_location = Some(self.location);
+        }
+        self.occupancy.remove(self.location);
+        self.location = self.next_location();
+        self.occupancy.insert(self.location);
+        if let Some(mut segment) = self.body.pop_back() {
+            let previous_location = self.previous_location.unwrap();
+            self.occupancy.remove(segment.location);
+            segment.location.x = previous_location.x;
+            segment.location.y = previous_location.y;
+            self.occupancy.insert(segment.location);
+            self.body.push_front(segment);
+        }
+    }
+
+    pub fn next_location(&self) -> Vector2 {
+        self.mode.step(&self.board, self.location, self.velocity)
+    }
+
+    pub fn draw(&mut self, console: &mut ConsoleContext, style: &Style, outline_only: bool) {
+        console.set_pixel(self.location.x, self.location.y, style.snake_head.pixel());
+        let tail_index = self.body.len().saturating_sub(1);
+        self.body
+            .iter()
+            .enumerate()
+            .for_each(|(index, body_segment)| {
+                if index == tail_index {
+                    body_segment.draw(console, style.snake_tail);
+                } else if outline_only && !self.occupancy.is_outline(body_segment.location) {
+                    body_segment.draw(console, style.snake_interior);
+                } else {
+                    body_segment.draw(console, style.snake_body);
+                }
+            });
+    }
+
+    pub fn hash_state(&self, hasher: &mut StateHasher) {
+        self.location.hash_state(hasher);
+        self.velocity.hash_state(hasher);
+        hasher.write_u64(self.body.len() as u64);
+        for segment in self.body.iter() {
+            segment.location.hash_state(hasher);
+        }
+    }
+
+    /// Drops up to `segments` segments off the end of the tail.
+    pub fn shrink(&mut self, segments: usize) {
+        for _ in 0..segments {
+            let Some(segment) = self.body.pop_back() else {
+                break;
+            };
+            self.occupancy.remove(segment.location);
+        }
+    }
+
+    // Whether the head has run into `other`: onto any part of it, or through
+    // its head as the two swapped places on the last move.
+    pub fn has_run_into(&self, other: &Snake) -> bool {
+        let swapped = self.previous_location == Some(other.location)
+            && other.previous_location == Some(self.location)
+            && self.velocity != Vector2::new(0, 0)
+            && other.velocity != Vector2::new(0, 0);
+        swapped || other.occupancy.contains(self.location)
+    }
+
+    pub fn grow(&mut self) {
+        if let Some(previous_location) = self.previous_location {
+            self.occupancy.insert(previous_location);
+            self.body
+                .push_front(BodySegment::new(previous_location.x, previous_location.y));
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct BodySegment {
+    pub location: Vector2,
+}
+
+impl BodySegment {
+    pub fn new(x: i32, y: i32) -> Self {
+        Self {
+            location: Vector2::new(x, y),
+        }
+    }
+
+    pub fn draw(&self, console: &mut ConsoleContext, glyph: Glyph) {
+        console.set_pixel(self.location.x, self.location.y, glyph.pixel());
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum FoodState {
+    Spawning { ticks_left: u32, delay: u32 },
+    Active,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum FoodKind {
+    #[default]
+    Normal,
+    /// Speeds the snake up for a while.
+    SpeedUp,
+    /// Slows the snake down for a while.
+    SlowDown,
+    /// Makes food worth two points for a while.
+    DoublePoints,
+    /// Takes a few segments off the tail instead of growing the snake.
+    Shrink,
+}
+
+impl FoodKind {
+    pub const SPECIAL: [FoodKind; 4] = [
+        FoodKind::SpeedUp,
+        FoodKind::SlowDown,
+        FoodKind::DoublePoints,
+        FoodKind::Shrink,
+    ];
+
+    fn glyph(self, style: &Style) -> Glyph {
+        match self {
+            FoodKind::Normal => style.food,
+            FoodKind::SpeedUp => style.food_speed_up,
+            FoodKind::SlowDown => style.food_slow_down,
+            FoodKind::DoublePoints => style.food_double_points,
+            FoodKind::Shrink => style.food_shrink,
+        }
+    }
+}
+
+impl fmt::Display for FoodKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            FoodKind::Normal => "Food",
+            FoodKind::SpeedUp => "Speed up",
+            FoodKind::SlowDown => "Slow down",
+            FoodKind::DoublePoints => "Double points",
+            FoodKind::Shrink => "Shrink",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A power-up's effect, which wears off after a number of moves.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct PowerUp {
+    kind: FoodKind,
+    moves_left: u32,
+}
+
+impl PowerUp {
+    fn tick(self) -> Option<Self> {
+        match self.moves_left {
+            0 | 1 => None,
+            moves_left => Some(Self {
+                moves_left: moves_left - 1,
+                ..self
+            }),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Food {
+    location: Vector2,
+    state: FoodState,
+    kind: FoodKind,
+}
+
+impl Food {
+    pub fn new(x: i32, y: i32) -> Self {
+        Self {
+            location: Vector2::new(x, y),
+            state: FoodState::Active,
+            kind: FoodKind::Normal,
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.state == FoodState::Active
+    }
+
+    pub fn start_spawning(&mut self, delay: u32) {
+        self.state = match delay {
+            0 => FoodState::Active,
+            delay => FoodState::Spawning {
+                ticks_left: delay,
+                delay,
+            },
+        };
+    }
+
+    /// Counts down one tick of spawning in.
+    pub fn tick(&mut self) {
+        if let FoodState::Spawning { ticks_left, delay } = self.state {
+            self.state = match ticks_left {
+                0 | 1 => FoodState::Active,
+                _ => FoodState::Spawning {
+                    ticks_left: ticks_left - 1,
+                    delay,
+                },
+            };
+        }
+    }
+
+    pub fn hash_state(&self, hasher: &mut StateHasher) {
+        self.location.hash_state(hasher);
+        hasher.write_u8(self.kind as u8);
+        match self.state {
+            FoodState::Spawning { ticks_left, delay } => {
+                hasher.write_u8(0);
+                hasher.write_u32(ticks_left);
+                hasher.write_u32(delay);
+            }
+            FoodState::Active => hasher.write_u8(1),
+        }
+    }
+
+    pub fn draw(&self, console: &mut ConsoleContext, style: &Style) {
+        let pixel = match self.state {
+            FoodState::Spawning { ticks_left, delay } if ticks_left * 2 > delay => {
+                pixel::pxl_fg('.', style.food_spawning.color)
+            }
+            FoodState::Spawning { .. } => style.food_spawning.pixel(),
+            FoodState::Active => self.kind.glyph(style).pixel(),
+        };
+        console.set_pixel(self.location.x, self.location.y, pixel);
+    }
+}
+
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub struct Vector2 {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Vector2 {
+    pub fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
+
+    pub fn add(&mut self, vector: Vector2) {
+        self.x += vector.x;
+        self.y += vector.y;
+    }
+
+    pub fn hash_state(&self, hasher: &mut StateHasher) {
+        hasher.write_i32(self.x);
+        hasher.write_i32(self.y);
+    }
+}
+
+/// The terminal: input and frame timing come from console_engine, while
+/// drawing goes through a `Screen` that writes each frame out in one go.
+pub struct ConsoleContext {
+    /// `None` for a headless console, which draws but never sees a key.
+    console: Option<ConsoleEngine>,
+    /// The keys pressed this frame and their modifiers, in the order they
+    /// arrived.
+    keys: Vec<(KeyCode, KeyModifiers)>,
+    holds: KeyHolds,
+    screen: Screen<Box<dyn Write>>,
+}
+
+impl ConsoleContext {
+    pub fn new(width: u16, height: u16, target_fps: u32) -> io::Result<Self> {
+        Ok(Self {
+            console: Some(ConsoleEngine::init(
+                width.into(),
+                height.into(),
+                target_fps,
+            )?),
+            keys: Vec::new(),
+            holds: KeyHolds::new(target_fps),
+            screen: Screen::new(width, height, Box::new(io::stdout())),
+        })
+    }
+
+    /// A console that draws into memory instead of the terminal and only
+    /// sees the keys it's handed, for embedding and for tests to look at
+    /// what was drawn.
+    pub fn headless(width: u16, height: u16) -> Self {
+        Self {
+            console: None,
+            keys: Vec::new(),
+            holds: KeyHolds::new(TICK_RATE),
+            screen: Screen::new(width, height, Box::new(io::sink())),
+        }
+    }
+
+    #[cfg(test)]
+    pub fn pixel(&self, x: i32, y: i32) -> Option<Pixel> {
+        self.screen.pixel(x, y)
+    }
+
+    /// Waits for the next frame, collecting the keys pressed on the way.
+    pub fn wait_for_frame(&mut self) {
+        let Some(console) = &mut self.console else {
+            return;
+        };
+        let mut keys = Vec::new();
+        loop {
+            match console.poll() {
+                Event::Frame => break,
+                Event::Key(key) => keys.push((key.code, key.modifiers)),
+                _ => (),
+            }
+        }
+        self.start_frame(keys);
+    }
+
+    fn start_frame(&mut self, keys: Vec<(KeyCode, KeyModifiers)>) {
+        self.holds.next_frame(keys.iter().map(|(code, _)| *code));
+        self.keys = keys;
+    }
+
+    /// Starts a frame on which `keys` were pressed, as if they'd come from
+    /// the terminal.
+    #[cfg(test)]
+    pub fn press(&mut self, keys: &[(KeyCode, KeyModifiers)]) {
+        self.start_frame(keys.to_vec());
+    }
+
+    pub fn clear_screen(&mut self) {
+        self.screen.fill(pixel::pxl(' '));
+    }
+
+    pub fn set_pixel(&mut self, x: i32, y: i32, character: Pixel) {
+        self.screen.set_pixel(x, y, character);
+    }
+
+    #[cfg(debug_assertions)]
+    pub fn clipped_writes(&self) -> u64 {
+        self.screen.clipped_writes()
+    }
+
+    pub fn skipped_presents(&self) -> u64 {
+        self.screen.skipped_presents()
+    }
+
+    /// How many bytes the last frame shown on the terminal took.
+    pub fn frame_bytes(&self) -> usize {
+        self.screen.frame_bytes()
+    }
+
+    /// Copies every cell of the frame being drawn into `renderer`.
+    pub fn render_into(&self, renderer: &mut dyn Renderer) {
+        for (x, y, pixel) in self.screen.cells() {
+            renderer.draw(x, y, pixel);
+        }
+    }
+
+    /// How many bytes have been written to the terminal in all.
+    pub fn bytes_written(&self) -> u64 {
+        self.screen.writer().bytes()
+    }
+
+    pub fn draw(&mut self) {
+        if let Err(error) = self.screen.present() {
+            warn!("Failed to draw a frame: {}", error);
+        }
+    }
+
+    pub fn is_key_pressed(&self, key: KeyCode) -> bool {
+        self.keys.contains(&(key, KeyModifiers::NONE))
+    }
+
+    pub fn is_interrupt_pressed(&self) -> bool {
+        self.keys
+            .contains(&(KeyCode::Char('c'), KeyModifiers::CONTROL))
+    }
+
+    /// The printable characters typed this frame, in the order they were
+    /// typed.
+    pub fn typed_chars(&self) -> Vec<char> {
+        self.keys
+            .iter()
+            .filter_map(|key| match *key {
+                (KeyCode::Char(character), KeyModifiers::NONE | KeyModifiers::SHIFT)
+                    if (' '..='~').contains(&character) =>
+                {
+                    Some(character)
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// How long `key` has been held, counted on the frame clock from the
+    /// frame its press was first seen.
+    pub fn held_duration(&self, key: KeyCode) -> Option<Duration> {
+        self.holds.held_duration(key)
+    }
+
+    /// Whether `key` was pressed this frame, or has been held long enough to
+    /// repeat like a menu key.
+    pub fn is_key_repeating(&self, key: KeyCode) -> bool {
+        self.is_key_pressed(key)
+            || self
+                .holds
+                .is_repeating(key, MENU_REPEAT_DELAY, MENU_REPEAT_INTERVAL)
+    }
+
+    pub fn fill(&mut self, pixel: Pixel) {
+        self.screen.fill(pixel);
+    }
+
+    pub fn print(&mut self, x: i32, y: i32, string: &str) {
+        self.screen.print(x, y, string, Color::Reset);
+    }
+
+    pub fn print_colored(&mut self, x: i32, y: i32, string: &str, color: Color) {
+        self.screen.print(x, y, string, color);
+    }
+
+    /// Writes debugging `diagnostics` over whatever shows next; see
+    /// [`Screen::overlay`].
+    #[cfg(debug_assertions)]
+    pub fn overlay(&mut self, x: i32, y: i32, diagnostics: String) {
+        self.screen.overlay(x, y, diagnostics);
+    }
+}
+
+impl Subcontext for ConsoleContext {}
+
+impl Drop for ConsoleContext {
+    fn drop(&mut self) {
+        let writer = self.screen.writer();
+        debug!(
+            "Wrote {} bytes to the terminal in {} writes and {} flushes",
+            writer.bytes(),
+            writer.writes(),
+            writer.flushes()
+        );
+    }
+}
+
+pub struct SimpleScheduler;
+
+impl Scheduler for SimpleScheduler {
+    fn update(&mut self, context: &mut Context, state: &mut dyn State) {
+        state.update(context);
+    }
+
+    fn render(&mut self, context: &mut Context, state: &mut dyn State) {
+        state.render(context);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn level(name: &str, map: &str, board: &Board) -> Level {
+        Level::parse(name, map, board).expect("the test level is valid")
+    }
+
+    #[test]
+    fn rematch_keeps_settings_but_not_the_seed() {
+        let board = Board::default();
+        let campaign = Campaign::new(vec![
+            level("first", "#.#\n.#.", &board),
+            level("second", "##", &board),
+        ])
+        .and_then(|campaign| campaign.next());
+        let config = RunConfig {
+            mode: GameMode::Walled,
+            difficulty: Difficulty::Fast,
+            score_decay_interval: Some(SCORE_DECAY_INTERVAL),
+            special_food_chance: 25,
+            campaign,
+            ..RunConfig::default()
+        }
+        .seeded(1);
+
+        let game = config.rematch().game();
+
+        assert_eq!(game.config.mode, GameMode::Walled);
+        assert_eq!(game.config.difficulty, Difficulty::Fast);
+        assert_eq!(game.config.score_decay_interval, Some(SCORE_DECAY_INTERVAL));
+        assert_eq!(game.config.special_food_chance, 25);
+        let level = game.config.level().expect("the rematch keeps its levels");
+        assert_eq!(level.walls().count(), 3);
+        assert_ne!(game.config.seed, Some(1));
+        assert!(!game.config.seeded);
+    }
+
+    #[test]
+    fn only_random_seeds_go_on_the_main_leaderboard() {
+        let config = RunConfig::default();
+        assert_eq!(config.leaderboard_seed(), None);
+        assert_eq!(config.rematch().leaderboard_seed(), None);
+        assert_eq!(config.seeded(7).leaderboard_seed(), Some(7));
+        assert_eq!(config.seeded(7).retry().leaderboard_seed(), Some(7));
+        assert_eq!(config.seeded(7).rematch().leaderboard_seed(), None);
+    }
+
+    #[test]
+    fn retry_keeps_the_seed() {
+        let game = RunConfig::default().seeded(7).retry().game();
+        assert_eq!(game.config.seed, Some(7));
+    }
+
+    fn sealed_pocket(right_pocket: &str) -> (RunConfig, Snake) {
+        // A 7x5 walled board with a wall down x = 3.  The snake fills the
+        // left pocket, leaving only the right one free.
+        let board = Board::new(7, 5).unwrap();
+        let map = format!("...#{0}\n...#{0}\n...#{0}", right_pocket);
+        let config = RunConfig {
+            board,
+            mode: GameMode::Walled,
+            campaign: Campaign::new(vec![level("sealed", &map, &board)]),
+            ..RunConfig::default()
+        };
+        let mut snake = Snake::new(1, 1, board, GameMode::Walled);
+        for (x, y) in [(1, 2), (1, 3), (2, 3), (2, 2), (2, 1)] {
+            snake.body.push_back(BodySegment::new(x, y));
+            snake.occupancy.insert(Vector2::new(x, y));
+        }
+        (config, snake)
+    }
+
+    #[test]
+    fn sealed_pockets_are_unreachable() {
+        let (config, snake) = sealed_pocket("..");
+        let blocked =
+            |location: Vector2| config.is_wall(location) || snake.occupancy.contains(location);
+        let reachable = reachable_locations(&config.board, snake.location, &blocked);
+        assert_eq!(reachable, HashSet::from([snake.location]));
+    }
+
+    #[test]
+    fn food_falls_back_to_unreachable_cells() {
+        let (config, snake) = sealed_pocket("..");
+        let mut rng = StdRng::seed_from_u64(3);
+        let location = find_food_location(&config, &[&snake], &mut rng)
+            .expect("there are free cells in the other pocket");
+        assert!(location.x >= 4 && location.x <= 5);
+        assert!(location.y >= 1 && location.y <= 3);
+    }
+
+    #[test]
+    fn food_finds_nowhere_on_a_full_board() {
+        let (config, snake) = sealed_pocket("##");
+        let mut rng = StdRng::seed_from_u64(3);
+        assert!(find_food_location(&config, &[&snake], &mut rng).is_none());
+    }
+
+    #[test]
+    fn food_prefers_reachable_cells() {
+        let (config, _) = sealed_pocket("..");
+        let head = Snake::new(1, 1, config.board, GameMode::Walled);
+        for seed in 0..20 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let location = find_food_location(&config, &[&head], &mut rng).unwrap();
+            assert!(location.x <= 2, "food spawned in the sealed pocket");
+            assert_ne!(location, head.location);
+        }
+    }
+
+    fn seeded_game(seed: u64) -> GameState {
+        RunConfig::default().seeded(seed).game()
+    }
+
+    #[test]
+    fn identical_states_hash_equal() {
+        let mut first = seeded_game(5);
+        let mut second = seeded_game(5);
+        assert_eq!(first.state_hash(), second.state_hash());
+        first.move_food();
+        second.move_food();
+        first.players[0].steering = Vector2::new(1, 0);
+        second.players[0].steering = Vector2::new(1, 0);
+        for _ in 0..10 {
+            first.step();
+            second.step();
+        }
+        assert_eq!(first.state_hash(), second.state_hash());
+    }
+
+    #[test]
+    fn one_step_changes_the_hash() {
+        let mut game = seeded_game(5);
+        let before = game.state_hash();
+        game.step();
+        assert_ne!(game.state_hash(), before);
+    }
+
+    #[test]
+    fn one_cell_changes_the_hash() {
+        let first = seeded_game(5);
+        let mut second = seeded_game(5);
+        second.food.location.x += 1;
+        assert_ne!(first.state_hash(), second.state_hash());
+    }
+
+    // Plays `steps` moves to the right, returning the hash before each one.
+    fn play_right(game: &mut GameState, steps: usize) -> Vec<u64> {
+        game.players[0].steering = Vector2::new(1, 0);
+        (0..steps)
+            .map(|_| {
+                let hash = game.state_hash();
+                game.step();
+                hash
+            })
+            .collect()
+    }
+
+    #[test]
+    fn practice_starts_from_the_rewind_point() {
+        let mut game = seeded_game(9);
+        let hashes = play_right(&mut game, 5);
+        let rewind_point = game.history.front().cloned().expect("the run has history");
+        let practice = game.config.clone().game().practicing_from(rewind_point);
+        assert_eq!(practice.state_hash(), hashes[0]);
+        assert!(practice.recording.is_none());
+    }
+
+    #[test]
+    fn practice_rewinds_to_its_start_when_the_run_ends() {
+        let mut game = seeded_game(9);
+        play_right(&mut game, 3);
+        let snapshot = game.snapshot();
+        let mut practice = game.config.clone().game().practicing_from(snapshot);
+        let start = practice.state_hash();
+        play_right(&mut practice, 4);
+        assert_ne!(practice.state_hash(), start);
+        assert!(practice.end_run(EndCause::WallCollision).is_none());
+        assert_eq!(practice.state_hash(), start);
+    }
+
+    // A run whose score decays by one every second, starting from `score`.
+    fn decaying_game(score: u32) -> GameState {
+        let mut game = RunConfig {
+            score_decay_interval: Some(Duration::from_secs(1)),
+            ..RunConfig::default()
+        }
+        .seeded(3)
+        .game();
+        game.players[0].score = score;
+        game.players[0].peak_score = score;
+        game
+    }
+
+    fn decay_at(game: &mut GameState, frame: u64) {
+        game.frames = frame;
+        game.decay_score();
+    }
+
+    #[test]
+    fn scores_decay_on_the_interval_boundary() {
+        let mut game = decaying_game(5);
+        decay_at(&mut game, u64::from(TICK_RATE) - 1);
+        assert_eq!(game.players[0].score, 5);
+        decay_at(&mut game, u64::from(TICK_RATE));
+        assert_eq!(game.players[0].score, 4);
+        decay_at(&mut game, u64::from(TICK_RATE) + 1);
+        assert_eq!(game.players[0].score, 4);
+        decay_at(&mut game, 2 * u64::from(TICK_RATE));
+        assert_eq!(game.players[0].score, 3);
+    }
+
+    #[test]
+    fn scores_never_decay_below_zero() {
+        let mut game = decaying_game(1);
+        decay_at(&mut game, u64::from(TICK_RATE));
+        decay_at(&mut game, 2 * u64::from(TICK_RATE));
+        assert_eq!(game.players[0].score, 0);
+    }
+
+    #[test]
+    fn scores_only_decay_when_enabled() {
+        let mut game = decaying_game(5);
+        game.config.score_decay_interval = None;
+        decay_at(&mut game, u64::from(TICK_RATE));
+        assert_eq!(game.players[0].score, 5);
+    }
+
+    #[test]
+    fn decay_keeps_the_peak_score() {
+        let mut game = decaying_game(5);
+        decay_at(&mut game, u64::from(TICK_RATE));
+        decay_at(&mut game, 2 * u64::from(TICK_RATE));
+        assert_eq!((game.players[0].score, game.players[0].peak_score), (3, 5));
+
+        while !game.food.is_active() {
+            game.food.tick();
+        }
+        game.food.location = game.players[0].snake.location;
+        game.step();
+        assert_eq!((game.players[0].score, game.players[0].peak_score), (4, 5));
+    }
+
+    fn settings_row(row: usize) -> SettingsState {
+        SettingsState {
+            selected_row: row,
+            ..SettingsState::default()
+        }
+    }
+
+    #[test]
+    fn vim_keys_are_refused_when_they_clash() {
+        let mut settings = Config::default();
+        settings.keys.up = KeyCode::Char('h');
+        let mut state = settings_row(3);
+        state.change(&mut settings, 1);
+        assert!(!settings.keys.vim_keys);
+        assert_eq!(
+            state.error.as_deref(),
+            Some("Vim keys would clash: \"h\" can't steer both up and left")
+        );
+
+        settings.keys.up = KeyCode::Up;
+        state.change(&mut settings, 1);
+        assert!(settings.keys.vim_keys);
+        assert_eq!(state.error, None);
+    }
+
+    #[test]
+    fn the_settings_screen_keeps_boards_playable() {
+        let mut settings = Config::default();
+        settings.board.width = config::MIN_PLAYABLE_WIDTH;
+        settings.board.height = config::MIN_PLAYABLE_HEIGHT;
+        settings_row(0).change(&mut settings, -1);
+        settings_row(1).change(&mut settings, -1);
+        assert_eq!(
+            (settings.board.width, settings.board.height),
+            (config::MIN_PLAYABLE_WIDTH, config::MIN_PLAYABLE_HEIGHT)
+        );
+        settings.board.width = board::MAX_BOARD_DIMENSION;
+        settings_row(0).change(&mut settings, 1);
+        assert_eq!(settings.board.width, board::MAX_BOARD_DIMENSION);
+    }
+
+    // The character drawn at each of `cells`.
+    fn drawn(console: &ConsoleContext, cells: &[Vector2]) -> String {
+        cells
+            .iter()
+            .map(|cell| console.pixel(cell.x, cell.y).map_or('?', |pixel| pixel.chr))
+            .collect()
+    }
+
+    #[test]
+    fn the_tail_tip_has_its_own_glyph_in_every_direction() {
+        let board = Board::new(11, 11).unwrap();
+        let style = Style::default();
+        for velocity in [
+            Vector2::new(0, -1),
+            Vector2::new(0, 1),
+            Vector2::new(-1, 0),
+            Vector2::new(1, 0),
+        ] {
+            // A head and three segments trailing straight behind it.
+            let cell = |offset: i32| Vector2::new(5 + velocity.x * offset, 5 + velocity.y * offset);
+            let mut snake = Snake::new(5, 5, board, GameMode::Walled);
+            snake.velocity = velocity;
+            for offset in 1..=3 {
+                snake
+                    .body
+                    .push_back(BodySegment::new(cell(-offset).x, cell(-offset).y));
+                snake.occupancy.insert(cell(-offset));
+            }
+            let mut console = ConsoleContext::headless(11, 11);
+            snake.draw(&mut console, &style, false);
+            assert_eq!(
+                drawn(&console, &[cell(0), cell(-1), cell(-2), cell(-3)]),
+                "@##+"
+            );
+
+            snake.update();
+            console.clear_screen();
+            snake.draw(&mut console, &style, false);
+            assert_eq!(
+                drawn(&console, &[cell(1), cell(0), cell(-1), cell(-2)]),
+                "@##+"
+            );
+            assert_eq!(drawn(&console, &[cell(-3)]), " ");
+        }
+    }
+
+    #[test]
+    fn the_title_screen_resets_the_exit_status() {
+        let exit_status = ExitStatus::default();
+        let mut context = Context::new();
+        context.add(exit_status.clone()).unwrap();
+        exit_status.set(ExitOutcome::Died);
+        TitleState::new(RunConfig::default()).setup(&mut context);
+        assert_eq!(exit_status.outcome(), ExitOutcome::Quit);
+    }
+
+    #[test]
+    fn starting_a_run_resets_the_exit_status() {
+        let exit_status = ExitStatus::default();
+        let mut context = Context::new();
+        context.add(exit_status.clone()).unwrap();
+        exit_status.set(ExitOutcome::Won);
+        RunConfig::default().game().setup(&mut context);
+        assert_eq!(exit_status.outcome(), ExitOutcome::Quit);
+    }
+
+    // The snake has moved into the cell food was about to appear in.
+    fn food_under_the_snake(seed: u64) -> GameState {
+        let mut game = seeded_game(seed);
+        assert!(game.respawn_food());
+        let food = game.food.location;
+        game.players[0].snake = Snake::new(food.x, food.y, game.config.board, game.config.mode);
+        game
+    }
+
+    #[test]
+    fn food_spawning_under_the_snake_is_rerolled() {
+        let mut game = food_under_the_snake(21);
+        let covered = game.food.location;
+        assert!(game.update_food());
+        assert_ne!(game.food.location, covered);
+        assert!(!game.players[0].snake.occupancy.contains(game.food.location));
+        assert!(!game.food.is_active());
+
+        let mut replayed = food_under_the_snake(21);
+        assert_eq!(replayed.food.location, covered);
+        assert!(replayed.update_food());
+        assert_eq!(replayed.food.location, game.food.location);
+    }
+
+    #[test]
+    fn food_spawning_in_a_free_cell_stays_put() {
+        let mut game = seeded_game(21);
+        assert!(game.respawn_food());
+        let location = game.food.location;
+        for _ in 0..game.config.food_spawn_delay {
+            assert!(game.update_food());
+            assert_eq!(game.food.location, location);
+        }
+        assert!(game.food.is_active());
+    }
+
+    #[test]
+    fn a_snake_partly_off_the_screen_draws_only_what_is_on_it() {
+        let board = Board::new(11, 11).unwrap();
+        let mut snake = Snake::new(3, 5, board, GameMode::Walled);
+        snake.velocity = Vector2::new(-1, 0);
+        for x in 4..=6 {
+            snake.body.push_back(BodySegment::new(x, 5));
+            snake.occupancy.insert(Vector2::new(x, 5));
+        }
+        let mut console = ConsoleContext::headless(5, 11);
+        snake.draw(&mut console, &Style::default(), false);
+        let row: Vec<Vector2> = (0..5).map(|x| Vector2::new(x, 5)).collect();
+        assert_eq!(drawn(&console, &row), "   @#");
+        #[cfg(debug_assertions)]
+        assert_eq!(console.clipped_writes(), 2);
+    }
+
+    #[test]
+    fn an_idle_quit_prompt_is_only_written_once() {
+        let mut game = seeded_game(3);
+        game.confirming_quit = true;
+        let mut console = ConsoleContext::headless(40, 20);
+        let style = Style::default();
+        for _ in 0..101 {
+            console.clear_screen();
+            game.draw(&mut console, &style);
+            console.draw();
+        }
+        assert_eq!(console.screen.writer().writes(), 1);
+        assert_eq!(console.skipped_presents(), 100);
+    }
+
+    #[test]
+    fn every_field_that_affects_the_run_changes_the_hash() {
+        type Change = (&'static str, fn(&mut GameState));
+        let changes: [Change; 16] = [
+            ("ticks", |game| game.ticks += 1),
+            ("frames", |game| game.frames += 1),
+            ("frames until move", |game| game.frames_until_move += 1),
+            ("steering", |game| {
+                game.players[0].steering = Vector2::new(0, 1)
+            }),
+            ("score", |game| game.players[0].score += 1),
+            ("peak score", |game| game.players[0].peak_score += 1),
+            ("board width", |game| {
+                game.config.board = Board::new(41, 20).unwrap()
+            }),
+            ("board height", |game| {
+                game.config.board = Board::new(40, 21).unwrap()
+            }),
+            ("mode", |game| game.config.mode = GameMode::Walled),
+            ("difficulty", |game| {
+                game.config.difficulty = Difficulty::Fast
+            }),
+            ("food spawn delay", |game| game.config.food_spawn_delay += 1),
+            ("special food chance", |game| {
+                game.config.special_food_chance += 1
+            }),
+            ("level points", |game| game.level_points += 1),
+            ("player", |game| {
+                game.players[0].snake.velocity = Vector2::new(-1, 0)
+            }),
+            ("player two", |game| {
+                let player = game.players[0].clone();
+                game.players.push(player)
+            }),
+            ("food", |game| game.food.location.x += 1),
+        ];
+        let game = || {
+            let mut game = seeded_game(9);
+            game.config.board = Board::new(40, 20).unwrap();
+            game
+        };
+        let unchanged = game().state_hash();
+        for (field, change) in changes {
+            let mut changed = game();
+            change(&mut changed);
+            assert_ne!(changed.state_hash(), unchanged, "{} isn't hashed", field);
+        }
+    }
+
+    #[test]
+    fn typed_characters_come_in_the_order_they_were_typed() {
+        let mut console = ConsoleContext::headless(10, 10);
+        console.press(&[
+            (KeyCode::Char('z'), KeyModifiers::NONE),
+            (KeyCode::Char('A'), KeyModifiers::SHIFT),
+            (KeyCode::Enter, KeyModifiers::NONE),
+            (KeyCode::Char('c'), KeyModifiers::CONTROL),
+            (KeyCode::Char('a'), KeyModifiers::NONE),
+            (KeyCode::Char('z'), KeyModifiers::NONE),
+        ]);
+        assert_eq!(console.typed_chars(), ['z', 'A', 'a', 'z']);
+        assert!(console.is_key_pressed(KeyCode::Enter));
+        assert!(console.is_interrupt_pressed());
+        assert!(!console.is_key_pressed(KeyCode::Char('c')));
+
+        console.press(&[]);
+        assert!(console.typed_chars().is_empty());
+        assert!(!console.is_key_pressed(KeyCode::Enter));
+    }
+
+    // Two levels worth a point each, with the snake one move from food on
+    // the first.
+    fn two_levels() -> GameState {
+        let board = Board::new(20, 10).unwrap();
+        let config = RunConfig {
+            board,
+            mode: GameMode::Walled,
+            campaign: Campaign::new(vec![
+                level("first", "goal: 1\n\n..@", &board),
+                level("second", "goal: 1\n\n\n\n.....@", &board),
+            ]),
+            ..RunConfig::default()
+        };
+        let mut game = config.seeded(4).game();
+        game.move_food();
+        game.food.location = Vector2::new(3, 2);
+        game.players[0].steering = Vector2::new(1, 0);
+        game
+    }
+
+    #[test]
+    fn reaching_the_goal_starts_the_next_level() {
+        let mut game = two_levels();
+        assert_eq!(game.players[0].snake.location, Vector2::new(2, 2));
+        game.step();
+        assert!(game.step().is_none());
+        let campaign = game.config.campaign.as_ref().expect("a level is played");
+        assert_eq!(campaign.number(), 2);
+        assert_eq!(game.players[0].snake.location, Vector2::new(5, 4));
+        assert!(game.players[0].snake.body.is_empty());
+        assert_eq!(game.players[0].snake.velocity, Vector2::new(0, 0));
+        assert_eq!((game.players[0].score, game.level_points), (1, 0));
+        assert!(game.history.is_empty());
+        assert!(!game.food.is_active());
+    }
+
+    #[test]
+    fn the_last_level_carries_on_past_its_goal() {
+        let mut game = two_levels();
+        game.step();
+        game.step();
+        game.food = Food::new(5, 4);
+        assert!(game.step().is_none());
+        assert_eq!(game.config.campaign.as_ref().unwrap().number(), 2);
+        assert_eq!((game.players[0].score, game.level_points), (2, 1));
+        assert!(!game.food.is_active());
+    }
+
+    // A versus game on an open 20x10 board with each player's snake laid
+    // out head first along `cells` and heading towards `heading`.
+    fn versus(mode: GameMode, snakes: [(&[(i32, i32)], Vector2); 2]) -> GameState {
+        let config = RunConfig {
+            board: Board::new(20, 10).unwrap(),
+            mode,
+            versus: true,
+            ..RunConfig::default()
+        };
+        let mut game = config.seeded(6).game();
+        for (player, (cells, heading)) in game.players.iter_mut().zip(snakes) {
+            let (x, y) = cells[0];
+            player.snake = Snake::new(x, y, game.config.board, mode);
+            for &(x, y) in &cells[1..] {
+                player.snake.body.push_back(BodySegment::new(x, y));
+                player.snake.occupancy.insert(Vector2::new(x, y));
+            }
+            player.snake.velocity = heading;
+            player.steering = heading;
+        }
+        game.food = Food::new(18, 8);
+        game
+    }
+
+    const LEFT: Vector2 = Vector2 { x: -1, y: 0 };
+    const RIGHT: Vector2 = Vector2 { x: 1, y: 0 };
+    const UP: Vector2 = Vector2 { x: 0, y: -1 };
+    const DOWN: Vector2 = Vector2 { x: 0, y: 1 };
+
+    // The headline of the results screen `transition` leads to.
+    fn results_headline(transition: OptionalTransition) -> String {
+        let Some(Transition::Push(mut state)) = transition else {
+            panic!("the round didn't end on a results screen");
+        };
+        let mut context = Context::new();
+        context.add(ConsoleContext::headless(60, 5)).unwrap();
+        state.render(&mut context);
+        let row: Vec<Vector2> = (0..60).map(|x| Vector2::new(x, 0)).collect();
+        drawn(get_console(&mut context), &row)
+            .trim_end()
+            .to_string()
+    }
+
+    fn round_result(game: &mut GameState) -> String {
+        results_headline((0..10).find_map(|_| game.step()))
+    }
+
+    const DRAW: &str = "Both snakes are out, it's a draw.";
+
+    #[test]
+    fn meeting_head_on_is_a_draw() {
+        let mut game = versus(GameMode::Classic, [(&[(5, 5)], RIGHT), (&[(7, 5)], LEFT)]);
+        assert!(game.step().is_none());
+        assert_eq!(game.crashed_players(), [true, true]);
+        assert_eq!(round_result(&mut game), DRAW);
+    }
+
+    #[test]
+    fn swapping_places_is_a_draw() {
+        let mut game = versus(GameMode::Classic, [(&[(5, 5)], RIGHT), (&[(6, 5)], LEFT)]);
+        assert!(game.step().is_none());
+        assert_eq!(game.players[0].snake.location, Vector2::new(6, 5));
+        assert_eq!(game.players[1].snake.location, Vector2::new(5, 5));
+        assert_eq!(game.crashed_players(), [true, true]);
+        assert_eq!(round_result(&mut game), DRAW);
+    }
+
+    #[test]
+    fn running_into_the_other_body_loses() {
+        let mut game = versus(
+            GameMode::Classic,
+            [
+                (&[(5, 4)], DOWN),
+                (&[(8, 5), (7, 5), (6, 5), (5, 5), (4, 5), (3, 5)], RIGHT),
+            ],
+        );
+        assert_eq!(round_result(&mut game), "Player 2 survived!");
+    }
+
+    #[test]
+    fn running_into_each_others_bodies_together_is_a_draw() {
+        let mut game = versus(
+            GameMode::Classic,
+            [
+                (&[(5, 4), (6, 4), (7, 4), (8, 4)], DOWN),
+                (&[(7, 5), (6, 5), (5, 5), (4, 5)], UP),
+            ],
+        );
+        assert!(game.step().is_none());
+        assert_eq!(game.crashed_players(), [true, true]);
+        assert_eq!(round_result(&mut game), DRAW);
+    }
+
+    #[test]
+    fn running_into_your_own_body_loses() {
+        let coiled = [(9, 3), (10, 3), (10, 4), (9, 4), (8, 4), (8, 3), (8, 2)];
+        let mut game = versus(GameMode::Classic, [(&[(5, 5)], RIGHT), (&coiled, LEFT)]);
+        assert!(game.step().is_none());
+        assert_eq!(game.crashed_players(), [false, true]);
+        assert_eq!(round_result(&mut game), "Player 1 survived!");
+    }
+
+    #[test]
+    fn running_into_a_wall_loses_and_together_is_a_draw() {
+        let mut game = versus(GameMode::Walled, [(&[(5, 5)], RIGHT), (&[(10, 1)], UP)]);
+        assert_eq!(round_result(&mut game), "Player 1 survived!");
+
+        let mut game = versus(GameMode::Walled, [(&[(18, 5)], RIGHT), (&[(10, 1)], UP)]);
+        assert_eq!(round_result(&mut game), DRAW);
+    }
+
+    #[test]
+    fn a_full_board_goes_to_the_higher_score() {
+        for (scores, headline) in [
+            ([3, 1], "Player 1 survived!"),
+            ([0, 2], "Player 2 survived!"),
+            ([2, 2], DRAW),
+        ] {
+            let mut game = versus(GameMode::Classic, [(&[(5, 5)], RIGHT), (&[(5, 7)], RIGHT)]);
+            for (player, score) in game.players.iter_mut().zip(scores) {
+                player.score = score;
+            }
+            assert_eq!(
+                results_headline(game.end_run(EndCause::BoardFull)),
+                headline
+            );
+        }
+    }
+
+    #[test]
+    fn versus_scores_decay_and_power_ups_act_on_both_snakes() {
+        let mut game = versus(GameMode::Classic, [(&[(5, 5)], RIGHT), (&[(5, 7)], RIGHT)]);
+        game.config.score_decay_interval = Some(Duration::from_secs(1));
+        for (player, score) in game.players.iter_mut().zip([3, 0]) {
+            player.score = score;
+        }
+        game.frames = u64::from(TICK_RATE);
+        game.decay_score();
+        assert_eq!((game.players[0].score, game.players[1].score), (2, 0));
+
+        game.food = Food::new(6, 7);
+        game.food.kind = FoodKind::SpeedUp;
+        let interval = game.move_interval();
+        game.step();
+        assert!(game.step().is_none());
+        assert_eq!(game.players[1].score, 1);
+        assert_eq!(game.players[1].snake.body.len(), 1);
+        assert!(game.power_up.is_some());
+        assert!(game.move_interval() < interval);
+    }
+}
@@ -0,0 +1,204 @@
+use std::collections::HashMap;
+
+use crate::board::Board;
+use crate::Vector2;
+
+// Tracks which cells the snake occupies along with how many of each cell's
+// neighbors are occupied, so the outline only changes where the snake moved.
+#[derive(Clone)]
+pub struct Occupancy {
+    board: Board,
+    counts: HashMap<Vector2, u32>,
+    occupied_neighbors: HashMap<Vector2, u8>,
+}
+
+impl Occupancy {
+    pub fn new(board: Board) -> Self {
+        Self {
+            board,
+            counts: HashMap::new(),
+            occupied_neighbors: HashMap::new(),
+        }
+    }
+
+    pub fn insert(&mut self, location: Vector2) {
+        let count = self.counts.entry(location).or_insert(0);
+        *count += 1;
+        if *count == 1 {
+            for neighbor in self.board.neighbors(location) {
+                *self.occupied_neighbors.entry(neighbor).or_insert(0) += 1;
+            }
+        }
+    }
+
+    pub fn remove(&mut self, location: Vector2) {
+        let Some(count) = self.counts.get_mut(&location) else {
+            return;
+        };
+        *count -= 1;
+        if *count == 0 {
+            self.counts.remove(&location);
+            for neighbor in self.board.neighbors(location) {
+                if let Some(occupied) = self.occupied_neighbors.get_mut(&neighbor) {
+                    *occupied -= 1;
+                    if *occupied == 0 {
+                        self.occupied_neighbors.remove(&neighbor);
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn contains(&self, location: Vector2) -> bool {
+        self.counts.contains_key(&location)
+    }
+
+    pub fn is_outline(&self, location: Vector2) -> bool {
+        self.occupied_neighbors
+            .get(&location)
+            .is_none_or(|occupied| *occupied < 4)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+    use std::time::Instant;
+
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    use super::*;
+
+    // The cells of a `width` by `height` block from `left`, `top`, in the
+    // order a snake coiling inwards clockwise would lie in them.
+    fn coil(left: i32, top: i32, width: i32, height: i32) -> Vec<Vector2> {
+        let (mut left, mut top) = (left, top);
+        let (mut right, mut bottom) = (left + width - 1, top + height - 1);
+        let mut cells = Vec::new();
+        while left <= right && top <= bottom {
+            cells.extend((left..=right).map(|x| Vector2::new(x, top)));
+            cells.extend((top + 1..=bottom).map(|y| Vector2::new(right, y)));
+            if top < bottom {
+                cells.extend((left..right).rev().map(|x| Vector2::new(x, bottom)));
+            }
+            if left < right {
+                cells.extend((top + 1..bottom).rev().map(|y| Vector2::new(left, y)));
+            }
+            (left, top, right, bottom) = (left + 1, top + 1, right - 1, bottom - 1);
+        }
+        cells
+    }
+
+    // '#' for the cells drawn in outline-only mode, 'o' for the hidden ones.
+    fn render(occupancy: &Occupancy) -> String {
+        let board = occupancy.board;
+        (0..board.height())
+            .map(|y| {
+                (0..board.width())
+                    .map(|x| match Vector2::new(x, y) {
+                        cell if !occupancy.contains(cell) => '.',
+                        cell if occupancy.is_outline(cell) => '#',
+                        _ => 'o',
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    // The neighbor counts worked out from scratch.
+    fn recomputed(occupancy: &Occupancy) -> HashMap<Vector2, u8> {
+        let mut occupied_neighbors = HashMap::new();
+        for location in occupancy.counts.keys() {
+            for neighbor in occupancy.board.neighbors(*location) {
+                *occupied_neighbors.entry(neighbor).or_insert(0) += 1;
+            }
+        }
+        occupied_neighbors
+    }
+
+    #[test]
+    fn a_coiled_snake_is_drawn_as_its_outline() {
+        let mut occupancy = Occupancy::new(Board::new(9, 7).unwrap());
+        let tail = [Vector2::new(1, 1), Vector2::new(2, 1)];
+        for cell in tail.into_iter().chain(coil(3, 1, 5, 5)) {
+            occupancy.insert(cell);
+        }
+        assert_eq!(
+            render(&occupancy),
+            [
+                ".........",
+                ".#######.",
+                "...#ooo#.",
+                "...#ooo#.",
+                "...#ooo#.",
+                "...#####.",
+                ".........",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn moving_keeps_the_counts_of_a_full_recompute() {
+        let board = Board::new(12, 8).unwrap();
+        let mut occupancy = Occupancy::new(board);
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut body = VecDeque::from([Vector2::new(5, 5)]);
+        occupancy.insert(Vector2::new(5, 5));
+        for step in 0..2_000 {
+            let head = board.neighbors(body[0])[rng.gen_range(0..4_usize)];
+            body.push_front(head);
+            occupancy.insert(head);
+            // Grow to 40 segments, then shrink back down now and then.
+            if body.len() > 40 || (step > 1_000 && step % 3 == 0) {
+                occupancy.remove(body.pop_back().unwrap());
+            }
+            assert_eq!(occupancy.occupied_neighbors, recomputed(&occupancy));
+        }
+        assert!(body.iter().all(|segment| occupancy.contains(*segment)));
+    }
+
+    #[test]
+    fn overlapping_segments_are_only_removed_once_all_have_left() {
+        let mut occupancy = Occupancy::new(Board::new(5, 5).unwrap());
+        occupancy.insert(Vector2::new(2, 2));
+        occupancy.insert(Vector2::new(2, 2));
+        occupancy.remove(Vector2::new(2, 2));
+        assert!(occupancy.contains(Vector2::new(2, 2)));
+        assert_eq!(occupancy.occupied_neighbors, recomputed(&occupancy));
+        occupancy.remove(Vector2::new(2, 2));
+        assert!(!occupancy.contains(Vector2::new(2, 2)));
+        assert!(occupancy.occupied_neighbors.is_empty());
+    }
+
+    // A benchmark rather than a check: `cargo test --release -- --ignored
+    // --nocapture` prints how long a 10,000 segment snake takes to move.
+    #[test]
+    #[ignore]
+    fn a_10k_segment_snake_moves_quickly() {
+        let board = Board::new(200, 100).unwrap();
+        let path = coil(0, 0, 200, 100);
+        let mut occupancy = Occupancy::new(board);
+        let mut body: VecDeque<Vector2> = path[..10_000].iter().copied().collect();
+        for segment in &body {
+            occupancy.insert(*segment);
+        }
+        let started = Instant::now();
+        for head in &path[10_000..] {
+            occupancy.insert(*head);
+            occupancy.remove(body.pop_front().unwrap());
+            body.push_back(*head);
+        }
+        let moves = path.len() - 10_000;
+        let elapsed = started.elapsed();
+        println!(
+            "{} moves in {:?} ({:?} per move)",
+            moves,
+            elapsed,
+            elapsed / moves as u32
+        );
+        assert!(elapsed.as_secs_f64() / (moves as f64) < 0.001);
+    }
+}
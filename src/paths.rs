@@ -0,0 +1,27 @@
+use std::env;
+use std::path::PathBuf;
+
+// Per-platform homes for console_snek's files, following the XDG base
+// directory spec on Linux and the usual per-user folders elsewhere.
+
+pub fn data_dir() -> Option<PathBuf> {
+    base_dir("XDG_DATA_HOME", ".local/share").map(|dir| dir.join(env!("CARGO_PKG_NAME")))
+}
+
+pub fn config_dir() -> Option<PathBuf> {
+    base_dir("XDG_CONFIG_HOME", ".config").map(|dir| dir.join(env!("CARGO_PKG_NAME")))
+}
+
+fn base_dir(xdg_variable: &str, home_fallback: &str) -> Option<PathBuf> {
+    if cfg!(windows) {
+        return env::var_os("APPDATA").map(PathBuf::from);
+    }
+    let home = env::var_os("HOME").map(PathBuf::from);
+    if cfg!(target_os = "macos") {
+        return home.map(|home| home.join("Library/Application Support"));
+    }
+    env::var_os(xdg_variable)
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| home.map(|home| home.join(home_fallback)))
+}
@@ -0,0 +1,226 @@
+use std::fmt;
+
+use crate::board::Board;
+use crate::Vector2;
+
+// Cells this close to the board edge count as hugging it.
+const EDGE_DISTANCE: i32 = 2;
+// At least half of the run spent along the edges.
+const EDGE_RIDER_FRACTION: f64 = 0.5;
+// At least 80% of 8+ turns made in the same rotation.
+const CIRCLER_MIN_TURNS: u32 = 8;
+const CIRCLER_ROTATION_FRACTION: f64 = 0.8;
+// 10+ turns, averaging fewer than 3 ticks apart.
+const CHAOS_MIN_TURNS: u32 = 10;
+const CHAOS_MAX_TICKS_BETWEEN_TURNS: f64 = 3.0;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PlayStyle {
+    EdgeRider,
+    Circler,
+    ChaosNoodle,
+    Wanderer,
+}
+
+impl fmt::Display for PlayStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::EdgeRider => "edge rider",
+            Self::Circler => "circler",
+            Self::ChaosNoodle => "chaos noodle",
+            Self::Wanderer => "wanderer",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct TurnStats {
+    pub up: u32,
+    pub down: u32,
+    pub left: u32,
+    pub right: u32,
+    pub clockwise: u32,
+    pub counter_clockwise: u32,
+    pub ticks: u64,
+    pub edge_ticks: u64,
+    ticks_between_turns: u64,
+    last_turn_tick: Option<u64>,
+}
+
+impl TurnStats {
+    /// Counts a change of direction from `from` to `to`.  The first move
+    /// from rest just sets off, so it isn't a turn.
+    pub fn record_turn(&mut self, from: Vector2, to: Vector2) {
+        if from == Vector2::new(0, 0) {
+            return;
+        }
+        match (to.x, to.y) {
+            (0, -1) => self.up += 1,
+            (0, 1) => self.down += 1,
+            (-1, 0) => self.left += 1,
+            (1, 0) => self.right += 1,
+            _ => return,
+        }
+        match from.x * to.y - from.y * to.x {
+            rotation if rotation > 0 => self.clockwise += 1,
+            rotation if rotation < 0 => self.counter_clockwise += 1,
+            _ => (),
+        }
+        if let Some(last_turn_tick) = self.last_turn_tick {
+            self.ticks_between_turns += self.ticks - last_turn_tick;
+        }
+        self.last_turn_tick = Some(self.ticks);
+    }
+
+    pub fn record_tick(&mut self, board: &Board, location: Vector2) {
+        self.ticks += 1;
+        if board.edge_distance(location) < EDGE_DISTANCE {
+            self.edge_ticks += 1;
+        }
+    }
+
+    pub fn turns(&self) -> u32 {
+        self.up + self.down + self.left + self.right
+    }
+
+    pub fn average_ticks_between_turns(&self) -> Option<f64> {
+        match self.turns() {
+            0 | 1 => None,
+            turns => Some(self.ticks_between_turns as f64 / (turns - 1) as f64),
+        }
+    }
+
+    pub fn edge_fraction(&self) -> f64 {
+        match self.ticks {
+            0 => 0.0,
+            ticks => self.edge_ticks as f64 / ticks as f64,
+        }
+    }
+
+    pub fn play_style(&self) -> PlayStyle {
+        let turns = self.turns();
+        let same_rotation = self.clockwise.max(self.counter_clockwise);
+        if self.edge_fraction() >= EDGE_RIDER_FRACTION {
+            PlayStyle::EdgeRider
+        } else if turns >= CIRCLER_MIN_TURNS
+            && same_rotation as f64 >= turns as f64 * CIRCLER_ROTATION_FRACTION
+        {
+            PlayStyle::Circler
+        } else if turns >= CHAOS_MIN_TURNS
+            && self
+                .average_ticks_between_turns()
+                .is_some_and(|average| average < CHAOS_MAX_TICKS_BETWEEN_TURNS)
+        {
+            PlayStyle::ChaosNoodle
+        } else {
+            PlayStyle::Wanderer
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UP: Vector2 = Vector2 { x: 0, y: -1 };
+    const DOWN: Vector2 = Vector2 { x: 0, y: 1 };
+    const LEFT: Vector2 = Vector2 { x: -1, y: 0 };
+    const RIGHT: Vector2 = Vector2 { x: 1, y: 0 };
+
+    fn board() -> Board {
+        Board::new(40, 20).unwrap()
+    }
+
+    // Starts moving right, then makes each turn `ticks_apart` ticks after
+    // the last, all in the middle of the board.
+    fn turning(directions: &[Vector2], ticks_apart: u64) -> TurnStats {
+        let mut stats = TurnStats::default();
+        let mut heading = RIGHT;
+        for direction in directions {
+            for _ in 0..ticks_apart {
+                stats.record_tick(&board(), Vector2::new(20, 10));
+            }
+            stats.record_turn(heading, *direction);
+            heading = *direction;
+        }
+        stats
+    }
+
+    // `clockwise` clockwise turns, then `counter` counter-clockwise ones,
+    // setting off to the right.
+    fn rotations(clockwise: usize, counter: usize) -> Vec<Vector2> {
+        let mut heading = RIGHT;
+        let mut turns = Vec::new();
+        for turn in 0..clockwise + counter {
+            heading = if turn < clockwise {
+                Vector2::new(-heading.y, heading.x)
+            } else {
+                Vector2::new(heading.y, -heading.x)
+            };
+            turns.push(heading);
+        }
+        turns
+    }
+
+    #[test]
+    fn turns_are_counted_by_direction() {
+        let stats = turning(&[DOWN, LEFT, UP, LEFT], 5);
+        assert_eq!(
+            (stats.up, stats.down, stats.left, stats.right),
+            (1, 1, 2, 0)
+        );
+        assert_eq!(stats.turns(), 4);
+        assert_eq!(stats.average_ticks_between_turns(), Some(5.0));
+    }
+
+    #[test]
+    fn setting_off_from_rest_is_not_a_turn() {
+        let mut stats = TurnStats::default();
+        stats.record_turn(Vector2::new(0, 0), RIGHT);
+        assert_eq!(stats.turns(), 0);
+        stats.record_turn(RIGHT, DOWN);
+        assert_eq!((stats.turns(), stats.down), (1, 1));
+    }
+
+    #[test]
+    fn rotations_are_counted() {
+        let stats = turning(&rotations(3, 2), 4);
+        assert_eq!((stats.clockwise, stats.counter_clockwise), (3, 2));
+    }
+
+    #[test]
+    fn edge_riders_spend_half_the_run_by_the_edges() {
+        let mut stats = TurnStats::default();
+        stats.record_tick(&board(), Vector2::new(1, 10));
+        stats.record_tick(&board(), Vector2::new(20, 10));
+        assert_eq!(stats.edge_fraction(), 0.5);
+        assert_eq!(stats.play_style(), PlayStyle::EdgeRider);
+        stats.record_tick(&board(), Vector2::new(20, 10));
+        assert_eq!(stats.play_style(), PlayStyle::Wanderer);
+    }
+
+    #[test]
+    fn circlers_make_most_turns_the_same_way() {
+        assert_eq!(
+            turning(&rotations(7, 1), 10).play_style(),
+            PlayStyle::Circler
+        );
+        assert_eq!(
+            turning(&rotations(6, 2), 10).play_style(),
+            PlayStyle::Wanderer
+        );
+        assert_eq!(
+            turning(&rotations(7, 0), 10).play_style(),
+            PlayStyle::Wanderer
+        );
+    }
+
+    #[test]
+    fn chaos_noodles_turn_often() {
+        let zigzag: Vec<Vector2> = [DOWN, RIGHT].into_iter().cycle().take(10).collect();
+        assert_eq!(turning(&zigzag, 2).play_style(), PlayStyle::ChaosNoodle);
+        assert_eq!(turning(&zigzag, 3).play_style(), PlayStyle::Wanderer);
+        assert_eq!(turning(&zigzag[..9], 2).play_style(), PlayStyle::Wanderer);
+    }
+}
@@ -0,0 +1,203 @@
+use console_engine::{Color, KeyCode};
+
+use crate::{ConsoleContext, TICK_RATE};
+
+// The cursor is shown for half a second, then hidden for half a second.
+const CURSOR_BLINK_FRAMES: u32 = TICK_RATE / 2;
+
+#[derive(PartialEq, Eq, Debug)]
+pub enum PromptResult {
+    Pending,
+    Confirmed(String),
+    Cancelled,
+}
+
+/// The keys a prompt reacts to on one frame.
+#[derive(Default)]
+pub struct PromptInput {
+    pub cancel: bool,
+    pub confirm: bool,
+    pub backspace: bool,
+    pub typed: Vec<char>,
+}
+
+impl PromptInput {
+    pub fn read(console: &ConsoleContext) -> Self {
+        Self {
+            cancel: console.is_key_pressed(KeyCode::Esc),
+            confirm: console.is_key_pressed(KeyCode::Enter),
+            backspace: console.is_key_pressed(KeyCode::Backspace),
+            typed: console.typed_chars(),
+        }
+    }
+}
+
+pub struct TextPrompt {
+    label: String,
+    text: String,
+    max_length: usize,
+    validator: fn(&str) -> Result<(), String>,
+    error: Option<String>,
+    frames: u32,
+}
+
+impl TextPrompt {
+    pub fn new(label: &str, max_length: usize) -> Self {
+        Self {
+            label: label.to_string(),
+            text: String::new(),
+            max_length,
+            validator: |_| Ok(()),
+            error: None,
+            frames: 0,
+        }
+    }
+
+    pub fn with_validator(mut self, validator: fn(&str) -> Result<(), String>) -> Self {
+        self.validator = validator;
+        self
+    }
+
+    pub fn update(&mut self, console: &ConsoleContext) -> PromptResult {
+        self.handle(PromptInput::read(console))
+    }
+
+    pub fn handle(&mut self, input: PromptInput) -> PromptResult {
+        self.frames = self.frames.wrapping_add(1);
+        if input.cancel {
+            return PromptResult::Cancelled;
+        }
+        if input.confirm {
+            match (self.validator)(&self.text) {
+                Ok(()) => return PromptResult::Confirmed(self.text.clone()),
+                Err(error) => self.error = Some(error),
+            }
+            return PromptResult::Pending;
+        }
+        if input.backspace && self.text.pop().is_some() {
+            self.error = None;
+        }
+        for character in input.typed {
+            if self.text.chars().count() < self.max_length {
+                self.text.push(character);
+                self.error = None;
+            }
+        }
+        PromptResult::Pending
+    }
+
+    fn cursor_visible(&self) -> bool {
+        (self.frames / CURSOR_BLINK_FRAMES).is_multiple_of(2)
+    }
+
+    pub fn draw(&self, console: &mut ConsoleContext, x: i32, y: i32) {
+        let cursor = if self.cursor_visible() { "_" } else { " " };
+        console.print(
+            x,
+            y,
+            format!("{}: {}{}", self.label, self.text, cursor).as_str(),
+        );
+        if let Some(error) = &self.error {
+            console.print_colored(x, y + 1, error, Color::Red);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn typed(text: &str) -> PromptInput {
+        PromptInput {
+            typed: text.chars().collect(),
+            ..PromptInput::default()
+        }
+    }
+
+    fn confirm() -> PromptInput {
+        PromptInput {
+            confirm: true,
+            ..PromptInput::default()
+        }
+    }
+
+    fn digits_only(text: &str) -> Result<(), String> {
+        if text.chars().all(|character| character.is_ascii_digit()) {
+            Ok(())
+        } else {
+            Err("Digits only".to_string())
+        }
+    }
+
+    #[test]
+    fn text_is_capped_at_the_max_length() {
+        let mut prompt = TextPrompt::new("Initials", 3);
+        prompt.handle(typed("ab"));
+        prompt.handle(typed("cde"));
+        assert_eq!(
+            prompt.handle(confirm()),
+            PromptResult::Confirmed("abc".to_string())
+        );
+    }
+
+    #[test]
+    fn backspace_removes_the_last_character() {
+        let mut prompt = TextPrompt::new("Initials", 3);
+        prompt.handle(typed("abc"));
+        prompt.handle(PromptInput {
+            backspace: true,
+            ..PromptInput::default()
+        });
+        prompt.handle(typed("z"));
+        assert_eq!(
+            prompt.handle(confirm()),
+            PromptResult::Confirmed("abz".to_string())
+        );
+    }
+
+    #[test]
+    fn invalid_text_is_not_confirmed() {
+        let mut prompt = TextPrompt::new("Seed", 10).with_validator(digits_only);
+        prompt.handle(typed("12x"));
+        assert_eq!(prompt.handle(confirm()), PromptResult::Pending);
+        assert_eq!(prompt.error.as_deref(), Some("Digits only"));
+        prompt.handle(PromptInput {
+            backspace: true,
+            ..PromptInput::default()
+        });
+        assert_eq!(prompt.error, None);
+        assert_eq!(
+            prompt.handle(confirm()),
+            PromptResult::Confirmed("12".to_string())
+        );
+    }
+
+    #[test]
+    fn escape_cancels_even_with_text_typed() {
+        let mut prompt = TextPrompt::new("Initials", 3);
+        prompt.handle(typed("ab"));
+        let result = prompt.handle(PromptInput {
+            cancel: true,
+            confirm: true,
+            ..PromptInput::default()
+        });
+        assert_eq!(result, PromptResult::Cancelled);
+    }
+
+    #[test]
+    fn the_cursor_blinks_every_half_second() {
+        let mut prompt = TextPrompt::new("Initials", 3);
+        let mut shown = Vec::new();
+        for _ in 0..TICK_RATE * 2 {
+            let mut console = ConsoleContext::headless(20, 2);
+            prompt.draw(&mut console, 0, 0);
+            shown.push(console.pixel(10, 0).unwrap().chr == '_');
+            prompt.handle(PromptInput::default());
+        }
+        let half_second = (TICK_RATE / 2) as usize;
+        for (half, frames) in shown.chunks(half_second).enumerate() {
+            let visible = half % 2 == 0;
+            assert!(frames.iter().all(|shown| *shown == visible));
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use console_engine::KeyCode;
+use wolf_engine::Transition;
+
+use crate::ConsoleContext;
+
+pub const QUIT_KEY: KeyCode = KeyCode::Char('q');
+
+// What the Quit action means for a kind of state. Ctrl+C always exits
+// immediately, regardless of the behavior.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum QuitBehavior {
+    Confirm,
+    Exit,
+    /// Returns to the previous screen.
+    Back,
+}
+
+pub enum QuitAction {
+    Confirm,
+    Transition(Transition),
+}
+
+impl QuitBehavior {
+    pub fn action(self, console: &ConsoleContext) -> Option<QuitAction> {
+        self.resolve(
+            console.is_interrupt_pressed(),
+            console.is_key_pressed(QUIT_KEY),
+        )
+    }
+
+    fn resolve(self, interrupted: bool, quit_pressed: bool) -> Option<QuitAction> {
+        if interrupted {
+            return Some(QuitAction::Transition(Transition::Quit));
+        }
+        if !quit_pressed {
+            return None;
+        }
+        Some(match self {
+            Self::Confirm => QuitAction::Confirm,
+            Self::Exit => QuitAction::Transition(Transition::Quit),
+            Self::Back => QuitAction::Transition(Transition::Pop),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, Eq)]
+    enum Outcome {
+        Nothing,
+        Confirm,
+        Quit,
+        Pop,
+    }
+
+    fn outcome(action: Option<QuitAction>) -> Outcome {
+        match action {
+            None => Outcome::Nothing,
+            Some(QuitAction::Confirm) => Outcome::Confirm,
+            Some(QuitAction::Transition(Transition::Quit)) => Outcome::Quit,
+            Some(QuitAction::Transition(Transition::Pop)) => Outcome::Pop,
+            Some(QuitAction::Transition(_)) => panic!("unexpected transition"),
+        }
+    }
+
+    #[test]
+    fn quit_follows_the_policy_for_each_kind_of_state() {
+        // (behavior, Ctrl+C, q, outcome)
+        let table = [
+            (QuitBehavior::Confirm, false, false, Outcome::Nothing),
+            (QuitBehavior::Confirm, false, true, Outcome::Confirm),
+            (QuitBehavior::Confirm, true, false, Outcome::Quit),
+            (QuitBehavior::Confirm, true, true, Outcome::Quit),
+            (QuitBehavior::Exit, false, false, Outcome::Nothing),
+            (QuitBehavior::Exit, false, true, Outcome::Quit),
+            (QuitBehavior::Exit, true, false, Outcome::Quit),
+            (QuitBehavior::Back, false, false, Outcome::Nothing),
+            (QuitBehavior::Back, false, true, Outcome::Pop),
+            (QuitBehavior::Back, true, false, Outcome::Quit),
+        ];
+        for (behavior, interrupted, quit_pressed, expected) in table {
+            assert_eq!(
+                outcome(behavior.resolve(interrupted, quit_pressed)),
+                expected,
+                "{:?} with Ctrl+C {} and q {}",
+                behavior,
+                interrupted,
+                quit_pressed
+            );
+        }
+    }
+}
@@ -0,0 +1,573 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+use console_engine::KeyCode;
+
+use crate::board::Board;
+use crate::config::{Direction, KeyBindings};
+use crate::game_mode::{Difficulty, GameMode};
+use crate::level::Campaign;
+use crate::state_hash::RULES_REVISION;
+use crate::{ConsoleContext, RunConfig, TICK_RATE, VERSUS_PLAYERS};
+
+pub const GROW_KEY: KeyCode = KeyCode::Char('g');
+
+const HEADER: &str = "console_snek replay";
+const FRAMES_HEADER: &str = "frames";
+const DIRECTION_BITS: [(Direction, u8); 4] = [
+    (Direction::Up, 1),
+    (Direction::Down, 2),
+    (Direction::Left, 4),
+    (Direction::Right, 8),
+];
+const GROW_BIT: u8 = 16;
+/// The longest run a replay can hold, a day of play.  Longer files are taken
+/// as corrupt rather than filling memory with their frames.
+const MAX_FRAMES: usize = 24 * 60 * 60 * TICK_RATE as usize;
+
+/// The keys that can change a run on one frame, packed into a byte.  Runs
+/// read these instead of the keyboard, so a recording plays back exactly.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct FrameInput(u8);
+
+impl FrameInput {
+    pub fn read(console: &ConsoleContext, keys: &KeyBindings) -> Self {
+        let mut bits = 0;
+        for (direction, bit) in DIRECTION_BITS {
+            if keys.is_pressed(console, direction) {
+                bits |= bit;
+            }
+        }
+        if console.is_key_pressed(GROW_KEY) {
+            bits |= GROW_BIT;
+        }
+        Self(bits)
+    }
+
+    pub fn is_pressed(self, direction: Direction) -> bool {
+        DIRECTION_BITS
+            .iter()
+            .any(|(pressed, bit)| *pressed == direction && self.0 & bit != 0)
+    }
+
+    pub fn grow(self) -> bool {
+        self.0 & GROW_BIT != 0
+    }
+}
+
+#[derive(Debug)]
+pub enum ReplayError {
+    Read(io::Error),
+    Invalid { line: usize, message: String },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Read(error) => write!(f, "it could not be read: {}", error),
+            Self::Invalid { line, message } => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+/// A recorded run: the settings and seed it started from and every player's
+/// input for every frame it ran, kept in a plain text file with runs of
+/// repeated input stored as `<frames> <input>` lines, one input per player.
+pub struct Replay {
+    seed: u64,
+    board: Board,
+    mode: GameMode,
+    difficulty: Difficulty,
+    score_decay_interval: Option<Duration>,
+    special_food_chance: u32,
+    food_spawn_delay: u32,
+    /// How many levels the run was played with, or 0 for the open board.
+    level_count: usize,
+    players: usize,
+    /// Each frame's input for every player in turn.
+    inputs: Vec<FrameInput>,
+    /// The run's state hash when it ended, used to check the playback.
+    state_hash: Option<u64>,
+}
+
+impl Replay {
+    pub fn new(config: &RunConfig) -> Self {
+        Self {
+            seed: config.seed.expect("runs pick their seed before recording"),
+            board: config.board,
+            mode: config.mode,
+            difficulty: config.difficulty,
+            score_decay_interval: config.score_decay_interval,
+            special_food_chance: config.special_food_chance,
+            food_spawn_delay: config.food_spawn_delay,
+            level_count: config.campaign.as_ref().map_or(0, Campaign::level_count),
+            players: config.player_count(),
+            inputs: Vec::new(),
+            state_hash: None,
+        }
+    }
+
+    /// Adds a frame on which each player gave the matching input.
+    pub fn record(&mut self, inputs: &[FrameInput]) {
+        debug_assert_eq!(inputs.len(), self.players);
+        self.inputs.extend_from_slice(inputs);
+    }
+
+    pub fn save(&self, path: &Path, state_hash: u64) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let (width, height) = self.board.size();
+        let mut contents = format!("{} {}\n", HEADER, RULES_REVISION);
+        contents.push_str(&format!("seed {}\n", self.seed));
+        contents.push_str(&format!("board {} {}\n", width, height));
+        contents.push_str(&format!("mode {}\n", self.mode.config_name()));
+        contents.push_str(&format!("difficulty {}\n", self.difficulty.config_name()));
+        contents.push_str(&format!(
+            "score_decay_ms {}\n",
+            self.score_decay_interval
+                .map_or(0, |interval| interval.as_millis())
+        ));
+        contents.push_str(&format!(
+            "special_food_chance {}\n",
+            self.special_food_chance
+        ));
+        contents.push_str(&format!("food_spawn_delay {}\n", self.food_spawn_delay));
+        contents.push_str(&format!("levels {}\n", self.level_count));
+        contents.push_str(&format!("players {}\n", self.players));
+        contents.push_str(&format!("state_hash {:016x}\n", state_hash));
+        contents.push_str(FRAMES_HEADER);
+        contents.push('\n');
+        let frames: Vec<&[FrameInput]> = self.inputs.chunks(self.players).collect();
+        for run in frames.chunk_by(|first, second| first == second) {
+            contents.push_str(&run.len().to_string());
+            for input in run[0] {
+                contents.push_str(&format!(" {}", input.0));
+            }
+            contents.push('\n');
+        }
+        fs::write(path, contents)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, ReplayError> {
+        let contents = fs::read_to_string(path).map_err(ReplayError::Read)?;
+        let mut lines = (1..).zip(contents.lines());
+        let invalid = |line: usize, message: String| ReplayError::Invalid { line, message };
+
+        let revision = lines
+            .next()
+            .and_then(|(_, line)| line.strip_prefix(HEADER))
+            .and_then(|revision| revision.trim().parse::<u32>().ok())
+            .ok_or_else(|| invalid(1, "this is not a console_snek replay".to_string()))?;
+        if revision != RULES_REVISION {
+            return Err(invalid(
+                1,
+                format!(
+                    "the run was recorded with rules revision {}, but this version plays revision {}",
+                    revision, RULES_REVISION
+                ),
+            ));
+        }
+
+        let mut replay = Self {
+            seed: 0,
+            board: Board::default(),
+            mode: GameMode::default(),
+            difficulty: Difficulty::default(),
+            score_decay_interval: None,
+            special_food_chance: 0,
+            food_spawn_delay: 0,
+            level_count: 0,
+            players: 1,
+            inputs: Vec::new(),
+            state_hash: None,
+        };
+        let mut seed = None;
+        let mut last_line = 1;
+        for (number, line) in lines.by_ref() {
+            last_line = number;
+            if line.trim() == FRAMES_HEADER {
+                break;
+            }
+            let (key, value) = line.trim().split_once(' ').ok_or_else(|| {
+                invalid(number, format!("expected `key value`, found \"{}\"", line))
+            })?;
+            let number_value = || {
+                value
+                    .parse::<u64>()
+                    .map_err(|_| invalid(number, format!("\"{}\" is not a number", value)))
+            };
+            let out_of_range =
+                || invalid(number, format!("\"{}\" is out of range for {}", value, key));
+            match key {
+                "seed" => seed = Some(number_value()?),
+                "board" => {
+                    let dimensions = value
+                        .split_once(' ')
+                        .and_then(|(width, height)| {
+                            Some((width.parse().ok()?, height.parse().ok()?))
+                        })
+                        .ok_or_else(|| {
+                            invalid(number, format!("\"{}\" is not a board size", value))
+                        })?;
+                    replay.board = Board::new(dimensions.0, dimensions.1)
+                        .map_err(|error| invalid(number, error.to_string()))?;
+                }
+                "mode" => {
+                    replay.mode = GameMode::parse(value)
+                        .ok_or_else(|| invalid(number, format!("unknown mode \"{}\"", value)))?
+                }
+                "difficulty" => {
+                    replay.difficulty = Difficulty::parse(value).ok_or_else(|| {
+                        invalid(number, format!("unknown difficulty \"{}\"", value))
+                    })?
+                }
+                "score_decay_ms" => {
+                    replay.score_decay_interval = match number_value()? {
+                        0 => None,
+                        millis => Some(Duration::from_millis(millis)),
+                    }
+                }
+                "special_food_chance" => {
+                    replay.special_food_chance = u32::try_from(number_value()?)
+                        .ok()
+                        .filter(|chance| *chance <= 100)
+                        .ok_or_else(out_of_range)?
+                }
+                "food_spawn_delay" => {
+                    replay.food_spawn_delay =
+                        u32::try_from(number_value()?).map_err(|_| out_of_range())?
+                }
+                "levels" => {
+                    replay.level_count =
+                        usize::try_from(number_value()?).map_err(|_| out_of_range())?
+                }
+                "players" => {
+                    replay.players = usize::try_from(number_value()?)
+                        .ok()
+                        .filter(|players| (1..=VERSUS_PLAYERS).contains(players))
+                        .ok_or_else(out_of_range)?
+                }
+                "state_hash" => {
+                    replay.state_hash = Some(u64::from_str_radix(value, 16).map_err(|_| {
+                        invalid(number, format!("\"{}\" is not a state hash", value))
+                    })?)
+                }
+                other => return Err(invalid(number, format!("unknown setting \"{}\"", other))),
+            }
+        }
+        replay.seed =
+            seed.ok_or_else(|| invalid(last_line, "the replay has no seed".to_string()))?;
+
+        let expected = format!("`<frames>{}`", " <input>".repeat(replay.players));
+        let mut total_frames = 0usize;
+        for (number, line) in lines {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let mut fields = line.split_whitespace();
+            let frames = fields
+                .next()
+                .and_then(|frames| frames.parse::<usize>().ok());
+            let inputs: Option<Vec<FrameInput>> = fields
+                .map(|input| input.parse::<u8>().ok().map(FrameInput))
+                .collect();
+            let run = frames.zip(inputs.filter(|inputs| inputs.len() == replay.players));
+            let Some((frames, inputs)) = run else {
+                return Err(invalid(
+                    number,
+                    format!("expected {}, found \"{}\"", expected, line),
+                ));
+            };
+            total_frames = total_frames
+                .checked_add(frames)
+                .filter(|total| *total <= MAX_FRAMES)
+                .ok_or_else(|| {
+                    invalid(
+                        number,
+                        format!("the run is longer than a day ({} frames)", MAX_FRAMES),
+                    )
+                })?;
+            for _ in 0..frames {
+                replay.inputs.extend_from_slice(&inputs);
+            }
+        }
+        Ok(replay)
+    }
+
+    pub fn board(&self) -> Board {
+        self.board
+    }
+
+    pub fn level_count(&self) -> usize {
+        self.level_count
+    }
+
+    pub fn inputs(&self) -> &[FrameInput] {
+        &self.inputs
+    }
+
+    /// Every player's input on frame `index`, or `None` past the end.
+    pub fn frame(&self, index: usize) -> Option<&[FrameInput]> {
+        self.inputs.chunks(self.players).nth(index)
+    }
+
+    pub fn state_hash(&self) -> Option<u64> {
+        self.state_hash
+    }
+
+    /// The config to play the recording back with, on `campaign` if the run
+    /// was played with levels.
+    pub fn config(&self, campaign: Option<Campaign>) -> RunConfig {
+        RunConfig {
+            board: self.board,
+            seed: Some(self.seed),
+            food_spawn_delay: self.food_spawn_delay,
+            mode: self.mode,
+            difficulty: self.difficulty,
+            score_decay_interval: self.score_decay_interval,
+            special_food_chance: self.special_food_chance,
+            campaign: campaign.filter(|_| self.level_count > 0),
+            versus: self.players > 1,
+            ..RunConfig::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::process;
+
+    use crate::{GameState, Vector2};
+
+    // A replay file of its own for each test, so tests can run in parallel.
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("console_snek_{}_{}.replay", process::id(), name))
+    }
+
+    fn load(name: &str, contents: &str) -> Result<Replay, ReplayError> {
+        let path = temp_path(name);
+        fs::write(&path, contents).expect("the replay is written");
+        let replay = Replay::load(&path);
+        fs::remove_file(&path).expect("the replay is removed");
+        replay
+    }
+
+    fn invalid_line(name: &str, contents: &str) -> Option<(usize, String)> {
+        match load(name, contents) {
+            Err(ReplayError::Invalid { line, message }) => Some((line, message)),
+            _ => None,
+        }
+    }
+
+    fn header(settings: &str) -> String {
+        format!(
+            "{} {}\n{}{}\n",
+            HEADER, RULES_REVISION, settings, FRAMES_HEADER
+        )
+    }
+
+    // Plays `inputs` from the start of a run, the way ReplayState does.
+    fn play(config: RunConfig, inputs: &[FrameInput]) -> GameState {
+        let mut game = config.game();
+        game.move_food();
+        for player in game.players.iter_mut() {
+            player.snake.velocity = Vector2::new(0, 0);
+            player.steering = player.snake.velocity;
+        }
+        for frame in inputs.chunks(game.players.len()) {
+            game.advance(frame);
+        }
+        game
+    }
+
+    fn recorded_inputs() -> Vec<FrameInput> {
+        let right = FrameInput(8);
+        let down = FrameInput(2);
+        let none = FrameInput::default();
+        let mut inputs = vec![right];
+        inputs.extend([none; 30]);
+        inputs.push(down);
+        inputs.extend([none; 20]);
+        inputs.push(FrameInput(GROW_BIT));
+        inputs.extend([none; 10]);
+        inputs
+    }
+
+    #[test]
+    fn recordings_load_back_and_replay_exactly() {
+        let config = RunConfig {
+            mode: GameMode::Walled,
+            difficulty: Difficulty::Fast,
+            score_decay_interval: Some(Duration::from_secs(15)),
+            special_food_chance: 25,
+            ..RunConfig::default()
+        }
+        .seeded(11);
+        let inputs = recorded_inputs();
+        let mut recording = Replay::new(&config);
+        for input in &inputs {
+            recording.record(&[*input]);
+        }
+        let recorded_hash = play(config, &inputs).state_hash();
+        let path = temp_path("round_trip");
+        recording
+            .save(&path, recorded_hash)
+            .expect("the replay saves");
+
+        let replay = Replay::load(&path);
+        fs::remove_file(&path).expect("the replay is removed");
+        let replay = replay.expect("the replay loads");
+        assert_eq!(replay.inputs(), inputs);
+        assert_eq!(replay.state_hash(), Some(recorded_hash));
+        let played = play(replay.config(None), replay.inputs());
+        assert_eq!(played.state_hash(), recorded_hash);
+    }
+
+    #[test]
+    fn versus_recordings_keep_both_players_input() {
+        let config = RunConfig {
+            versus: true,
+            special_food_chance: 25,
+            ..RunConfig::default()
+        }
+        .seeded(5);
+        let first = recorded_inputs();
+        let mut second = vec![FrameInput(4), FrameInput(4)];
+        second.extend([FrameInput::default(); 20]);
+        second.push(FrameInput(1));
+        second.resize(first.len(), FrameInput::default());
+        let inputs: Vec<FrameInput> = first
+            .iter()
+            .zip(&second)
+            .flat_map(|(first, second)| [*first, *second])
+            .collect();
+        let mut recording = Replay::new(&config);
+        for frame in inputs.chunks(2) {
+            recording.record(frame);
+        }
+        let recorded_hash = play(config, &inputs).state_hash();
+        let path = temp_path("versus_round_trip");
+        recording
+            .save(&path, recorded_hash)
+            .expect("the replay saves");
+
+        let replay = Replay::load(&path);
+        fs::remove_file(&path).expect("the replay is removed");
+        let replay = replay.expect("the replay loads");
+        assert_eq!(replay.frame(1), Some([first[1], second[1]].as_slice()));
+        assert_eq!(replay.frame(first.len()), None);
+        let config = replay.config(None);
+        assert!(config.versus);
+        assert_eq!(play(config, replay.inputs()).state_hash(), recorded_hash);
+    }
+
+    #[test]
+    fn versus_frame_runs_need_an_input_for_each_player() {
+        let contents = header("seed 1\nplayers 2\n") + "3 0 8\n3 8\n";
+        assert_eq!(
+            invalid_line("one_input", &contents),
+            Some((
+                6,
+                "expected `<frames> <input> <input>`, found \"3 8\"".to_string()
+            ))
+        );
+        let contents = header("seed 1\nplayers 3\n");
+        assert_eq!(
+            invalid_line("three_players", &contents),
+            Some((3, "\"3\" is out of range for players".to_string()))
+        );
+    }
+
+    #[test]
+    fn only_replays_load() {
+        assert_eq!(
+            invalid_line("not_a_replay", "[board]\nwidth = 80\n"),
+            Some((1, "this is not a console_snek replay".to_string()))
+        );
+    }
+
+    #[test]
+    fn replays_from_other_rules_revisions_are_refused() {
+        let contents = format!("{} {}\nseed 1\n", HEADER, RULES_REVISION + 1);
+        let (line, message) =
+            invalid_line("other_revision", &contents).expect("the replay is refused");
+        assert_eq!(line, 1);
+        assert!(message.contains(&format!("rules revision {}", RULES_REVISION + 1)));
+    }
+
+    #[test]
+    fn replays_need_a_seed() {
+        assert_eq!(
+            invalid_line("no_seed", &header("board 80 20\n")),
+            Some((3, "the replay has no seed".to_string()))
+        );
+    }
+
+    #[test]
+    fn bad_settings_name_their_line() {
+        assert_eq!(
+            invalid_line("bad_board", &header("seed 1\nboard 0 20\n")),
+            Some((3, "the board width must be at least 1".to_string()))
+        );
+        assert_eq!(
+            invalid_line("bad_setting", &header("seed 1\nspeed 3\n")),
+            Some((3, "unknown setting \"speed\"".to_string()))
+        );
+    }
+
+    #[test]
+    fn bad_frame_runs_name_their_line() {
+        let contents = header("seed 1\n") + "3 0\nthree 0\n";
+        assert_eq!(
+            invalid_line("bad_run", &contents),
+            Some((
+                5,
+                "expected `<frames> <input>`, found \"three 0\"".to_string()
+            ))
+        );
+        let contents = header("seed 1\n") + "3 256\n";
+        assert!(invalid_line("bad_input", &contents).is_some());
+    }
+
+    #[test]
+    fn overlong_runs_are_refused() {
+        let too_long = format!("the run is longer than a day ({} frames)", MAX_FRAMES);
+        let contents = header("seed 1\n") + &format!("{} 0\n", usize::MAX);
+        assert_eq!(
+            invalid_line("huge_run", &contents),
+            Some((4, too_long.clone()))
+        );
+        let contents = header("seed 1\n") + &format!("{} 0\n1 8\n", MAX_FRAMES);
+        assert_eq!(invalid_line("long_runs", &contents), Some((5, too_long)));
+        let contents = header("seed 1\n") + &format!("{} 0\n", MAX_FRAMES);
+        assert!(load("longest_run", &contents).is_ok());
+    }
+
+    #[test]
+    fn out_of_range_settings_are_refused() {
+        for (setting, value) in [
+            ("special_food_chance", "101"),
+            ("special_food_chance", "4294967396"),
+            ("food_spawn_delay", "4294967296"),
+        ] {
+            let contents = header(&format!("seed 1\n{} {}\n", setting, value));
+            assert_eq!(
+                invalid_line(setting, &contents),
+                Some((3, format!("\"{}\" is out of range for {}", value, setting)))
+            );
+        }
+        let replay = load(
+            "in_range",
+            &header("seed 1\nspecial_food_chance 100\nfood_spawn_delay 4294967295\n"),
+        )
+        .expect("the replay loads");
+        assert_eq!(replay.special_food_chance, 100);
+        assert_eq!(replay.food_spawn_delay, u32::MAX);
+    }
+}
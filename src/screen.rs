@@ -0,0 +1,382 @@
+use std::io::{self, Write};
+
+use console_engine::pixel::{self, Pixel};
+use console_engine::Color;
+
+/// Counts the writes, flushes, and bytes that pass through to `inner`.
+pub struct CountingWriter<W> {
+    inner: W,
+    writes: u64,
+    flushes: u64,
+    bytes: u64,
+}
+
+impl<W> CountingWriter<W> {
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            writes: 0,
+            flushes: 0,
+            bytes: 0,
+        }
+    }
+
+    pub fn writes(&self) -> u64 {
+        self.writes
+    }
+
+    pub fn flushes(&self) -> u64 {
+        self.flushes
+    }
+
+    pub fn bytes(&self) -> u64 {
+        self.bytes
+    }
+}
+
+impl<W: Write> Write for CountingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.writes += 1;
+        self.bytes += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.flushes += 1;
+        self.inner.flush()
+    }
+}
+
+/// The picture being drawn this frame, and the one last shown on the
+/// terminal.  Presenting a frame sends only the cells that changed, as one
+/// buffered write and flush, however many cells that is.
+pub struct Screen<W> {
+    width: i32,
+    height: i32,
+    frame: Vec<Pixel>,
+    /// The last frame shown, as drawn before the overlay went on top.
+    drawn: Option<Vec<Pixel>>,
+    /// The last frame shown, overlay and all.
+    presented: Option<Vec<Pixel>>,
+    overlay: Option<(i32, i32, String)>,
+    out: CountingWriter<W>,
+    buffer: Vec<u8>,
+    /// How many bytes the last presented frame took.
+    frame_bytes: usize,
+    /// Cells drawn off the edge of the screen, which are a bug worth
+    /// seeing while developing but not worth counting in a release build.
+    #[cfg(debug_assertions)]
+    clipped_writes: u64,
+    skipped_presents: u64,
+}
+
+impl<W: Write> Screen<W> {
+    pub fn new(width: u16, height: u16, out: W) -> Self {
+        Self {
+            width: width.into(),
+            height: height.into(),
+            frame: vec![pixel::pxl(' '); usize::from(width) * usize::from(height)],
+            drawn: None,
+            presented: None,
+            overlay: None,
+            out: CountingWriter::new(out),
+            buffer: Vec::new(),
+            frame_bytes: 0,
+            #[cfg(debug_assertions)]
+            clipped_writes: 0,
+            skipped_presents: 0,
+        }
+    }
+
+    pub fn set_pixel(&mut self, x: i32, y: i32, pixel: Pixel) {
+        match self.index(x, y) {
+            Some(index) => self.frame[index] = pixel,
+            #[cfg(debug_assertions)]
+            None => self.clipped_writes += 1,
+            #[cfg(not(debug_assertions))]
+            None => (),
+        }
+    }
+
+    pub fn fill(&mut self, pixel: Pixel) {
+        self.frame.fill(pixel);
+    }
+
+    /// Writes `string` from `x`, `y` onwards, cutting off whatever runs past
+    /// the right edge.
+    pub fn print(&mut self, x: i32, y: i32, string: &str, fg: Color) {
+        for (offset, character) in (0..).zip(string.chars()) {
+            if let Some(index) = self.index(x + offset, y) {
+                self.frame[index] = pixel::pxl_fbg(character, fg, Color::Reset);
+            }
+        }
+    }
+
+    /// Writes `string` over the next frame presented.  Unlike the frame,
+    /// the overlay changing doesn't make an otherwise idle frame worth
+    /// presenting, so diagnostics about presenting don't keep the
+    /// terminal awake themselves.
+    #[cfg(debug_assertions)]
+    pub fn overlay(&mut self, x: i32, y: i32, string: String) {
+        self.overlay = Some((x, y, string));
+    }
+
+    /// Shows the frame on the terminal, unless it's identical to the one
+    /// already there.
+    pub fn present(&mut self) -> io::Result<()> {
+        let overlay = self.overlay.take();
+        if self.drawn.as_ref() == Some(&self.frame) {
+            self.skipped_presents += 1;
+            return Ok(());
+        }
+        let mut composed = self.frame.clone();
+        if let Some((x, y, string)) = overlay {
+            for (offset, character) in (0..).zip(string.chars()) {
+                if let Some(index) = self.index(x + offset, y) {
+                    composed[index] = pixel::pxl_fbg(character, Color::Reset, Color::Reset);
+                }
+            }
+        }
+        self.buffer.clear();
+        encode(
+            &mut self.buffer,
+            self.presented.as_deref(),
+            &composed,
+            self.width as usize,
+        );
+        self.frame_bytes = self.buffer.len();
+        self.out.write_all(&self.buffer)?;
+        self.out.flush()?;
+        self.drawn = Some(self.frame.clone());
+        self.presented = Some(composed);
+        Ok(())
+    }
+
+    /// The cell at `x`, `y` in the frame being drawn.
+    #[cfg(test)]
+    pub fn pixel(&self, x: i32, y: i32) -> Option<Pixel> {
+        self.index(x, y).map(|index| self.frame[index])
+    }
+
+    /// Every cell of the frame being drawn and where it goes, row by row.
+    pub fn cells(&self) -> impl Iterator<Item = (u16, u16, Pixel)> + '_ {
+        let width = self.width as usize;
+        self.frame
+            .iter()
+            .enumerate()
+            .map(move |(index, pixel)| ((index % width) as u16, (index / width) as u16, *pixel))
+    }
+
+    pub fn frame_bytes(&self) -> usize {
+        self.frame_bytes
+    }
+
+    pub fn writer(&self) -> &CountingWriter<W> {
+        &self.out
+    }
+
+    #[cfg(debug_assertions)]
+    pub fn clipped_writes(&self) -> u64 {
+        self.clipped_writes
+    }
+
+    pub fn skipped_presents(&self) -> u64 {
+        self.skipped_presents
+    }
+
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        if x < 0 || y < 0 || x >= self.width || y >= self.height {
+            return None;
+        }
+        usize::try_from(y * self.width + x).ok()
+    }
+}
+
+// Appends the escape sequences that turn `previous` into `frame` on the
+// terminal, or draw all of `frame` when nothing has been shown yet.  Cursor
+// moves and color changes are only sent when the next changed cell needs
+// them.
+fn encode(buffer: &mut Vec<u8>, previous: Option<&[Pixel]>, frame: &[Pixel], width: usize) {
+    let mut cursor = None;
+    let mut colors = None;
+    for (index, cell) in frame.iter().enumerate() {
+        if previous.is_some_and(|previous| previous[index] == *cell) {
+            continue;
+        }
+        let (x, y) = (index % width, index / width);
+        if cursor != Some(index) {
+            buffer.extend_from_slice(format!("\x1b[{};{}H", y + 1, x + 1).as_bytes());
+        }
+        if colors != Some((cell.fg, cell.bg)) {
+            buffer.extend_from_slice(
+                format!(
+                    "\x1b[{};{}m",
+                    color_code(cell.fg, 38),
+                    color_code(cell.bg, 48)
+                )
+                .as_bytes(),
+            );
+            colors = Some((cell.fg, cell.bg));
+        }
+        let mut utf8 = [0; 4];
+        buffer.extend_from_slice(cell.chr.encode_utf8(&mut utf8).as_bytes());
+        // Where the cursor lands after the last column depends on the
+        // terminal, so the next row always moves it explicitly.
+        cursor = (x + 1 < width).then_some(index + 1);
+    }
+}
+
+// The SGR parameters for `color`, with `base` 38 for the foreground or 48
+// for the background.
+fn color_code(color: Color, base: u8) -> String {
+    let ansi = match color {
+        Color::Reset => return (base + 1).to_string(),
+        Color::Rgb { r, g, b } => return format!("{};2;{};{};{}", base, r, g, b),
+        Color::AnsiValue(value) => value,
+        Color::Black => 0,
+        Color::DarkRed => 1,
+        Color::DarkGreen => 2,
+        Color::DarkYellow => 3,
+        Color::DarkBlue => 4,
+        Color::DarkMagenta => 5,
+        Color::DarkCyan => 6,
+        Color::Grey => 7,
+        Color::DarkGrey => 8,
+        Color::Red => 9,
+        Color::Green => 10,
+        Color::Yellow => 11,
+        Color::Blue => 12,
+        Color::Magenta => 13,
+        Color::Cyan => 14,
+        Color::White => 15,
+    };
+    format!("{};5;{}", base, ansi)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn screen(width: u16, height: u16) -> Screen<Vec<u8>> {
+        Screen::new(width, height, Vec::new())
+    }
+
+    fn output(screen: &Screen<Vec<u8>>) -> String {
+        String::from_utf8(screen.out.inner.clone()).expect("the output is UTF-8")
+    }
+
+    #[test]
+    fn each_frame_is_one_write_and_one_flush() {
+        let mut screen = screen(10, 4);
+        screen.fill(pixel::pxl('.'));
+        screen.print(1, 1, "snake", Color::Green);
+        screen.present().expect("the frame is written");
+        assert_eq!(screen.writer().writes(), 1);
+        assert_eq!(screen.writer().flushes(), 1);
+
+        for x in 0..10 {
+            screen.set_pixel(x, 3, pixel::pxl_fg('#', Color::Grey));
+        }
+        screen.present().expect("the frame is written");
+        assert_eq!(screen.writer().writes(), 2);
+        assert_eq!(screen.writer().flushes(), 2);
+    }
+
+    #[test]
+    fn frame_bytes_count_what_was_written() {
+        let mut screen = screen(10, 4);
+        screen.present().expect("the frame is written");
+        let first = screen.frame_bytes();
+        assert_eq!(screen.writer().bytes(), first as u64);
+
+        screen.set_pixel(2, 1, pixel::pxl('@'));
+        screen.present().expect("the frame is written");
+        assert!(screen.frame_bytes() < first);
+        assert_eq!(
+            screen.writer().bytes(),
+            (first + screen.frame_bytes()) as u64
+        );
+    }
+
+    #[test]
+    fn only_changed_cells_are_sent() {
+        let mut screen = screen(4, 2);
+        screen.present().expect("the frame is written");
+        let shown = output(&screen).len();
+
+        screen.set_pixel(1, 1, pixel::pxl_fg('@', Color::Red));
+        screen.set_pixel(2, 1, pixel::pxl_fg('#', Color::Red));
+        screen.present().expect("the frame is written");
+        assert_eq!(&output(&screen)[shown..], "\x1b[2;2H\x1b[38;5;9;49m@#");
+    }
+
+    #[test]
+    fn the_first_frame_draws_every_cell() {
+        let mut screen = screen(2, 2);
+        screen.fill(pixel::pxl('.'));
+        screen.present().expect("the frame is written");
+        assert_eq!(output(&screen), "\x1b[1;1H\x1b[39;49m..\x1b[2;1H..");
+    }
+
+    #[test]
+    fn colors_map_to_ansi_codes() {
+        assert_eq!(color_code(Color::Reset, 38), "39");
+        assert_eq!(color_code(Color::Reset, 48), "49");
+        assert_eq!(color_code(Color::DarkGreen, 38), "38;5;2");
+        assert_eq!(color_code(Color::White, 48), "48;5;15");
+        assert_eq!(color_code(Color::AnsiValue(200), 38), "38;5;200");
+        assert_eq!(
+            color_code(Color::Rgb { r: 1, g: 2, b: 3 }, 48),
+            "48;2;1;2;3"
+        );
+    }
+
+    #[test]
+    fn cells_off_the_screen_are_clipped_and_counted() {
+        let mut screen = screen(3, 2);
+        for x in -1..=3 {
+            screen.set_pixel(x, 1, pixel::pxl('#'));
+        }
+        screen.set_pixel(1, -1, pixel::pxl('#'));
+        screen.set_pixel(1, 2, pixel::pxl('#'));
+        #[cfg(debug_assertions)]
+        assert_eq!(screen.clipped_writes(), 4);
+        let row: String = (0..3).map(|x| screen.pixel(x, 1).unwrap().chr).collect();
+        assert_eq!(row, "###");
+        assert_eq!(screen.pixel(1, 0).unwrap().chr, ' ');
+    }
+
+    #[test]
+    fn identical_frames_are_skipped() {
+        let mut screen = screen(10, 4);
+        screen.print(1, 1, "paused", Color::Reset);
+        for _ in 0..101 {
+            screen.present().expect("the frame is written");
+        }
+        assert_eq!(screen.writer().writes(), 1);
+        assert_eq!(screen.skipped_presents(), 100);
+
+        screen.print(1, 2, "!", Color::Reset);
+        screen.present().expect("the frame is written");
+        assert_eq!(screen.writer().writes(), 2);
+        assert_eq!(screen.skipped_presents(), 100);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    fn the_overlay_alone_does_not_present_a_frame() {
+        let mut screen = screen(4, 1);
+        screen.overlay(0, 0, "1".to_string());
+        screen.present().expect("the frame is written");
+        screen.overlay(0, 0, "2".to_string());
+        screen.present().expect("the frame is written");
+        assert_eq!(screen.skipped_presents(), 1);
+        assert_eq!(output(&screen), "\x1b[1;1H\x1b[39;49m1   ");
+
+        screen.overlay(0, 0, "3".to_string());
+        screen.set_pixel(3, 0, pixel::pxl('#'));
+        screen.present().expect("the frame is written");
+        assert!(output(&screen).ends_with("\x1b[1;1H\x1b[39;49m3\x1b[1;4H#"));
+    }
+}
@@ -0,0 +1,118 @@
+// Crockford's base32 alphabet, which leaves out I, L, O and U so shared
+// seeds are hard to mistype.
+const BASE32_ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+pub const SHARE_PREFIX: char = '#';
+
+pub fn encode_seed(seed: u64) -> String {
+    let mut digits = Vec::new();
+    let mut remaining = seed;
+    loop {
+        digits.push(BASE32_ALPHABET[(remaining % 32) as usize] as char);
+        remaining /= 32;
+        if remaining == 0 {
+            break;
+        }
+    }
+    std::iter::once(SHARE_PREFIX)
+        .chain(digits.into_iter().rev())
+        .collect()
+}
+
+// Accepts decimal, 0x-prefixed hexadecimal, and the #-prefixed base32 form
+// returned by `encode_seed`.
+pub fn parse_seed(text: &str) -> Result<u64, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err("enter a seed".into());
+    }
+    if let Some(share) = text.strip_prefix(SHARE_PREFIX) {
+        return parse_base32(share);
+    }
+    if let Some(hex) = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        return u64::from_str_radix(hex, 16).map_err(|_| {
+            format!(
+                "\"{}\" is not a hex seed between 0x0 and 0x{:X}",
+                text,
+                u64::MAX
+            )
+        });
+    }
+    text.parse()
+        .map_err(|_| format!("\"{}\" is not a seed between 0 and {}", text, u64::MAX))
+}
+
+fn parse_base32(text: &str) -> Result<u64, String> {
+    if text.is_empty() {
+        return Err("enter the seed after the #".into());
+    }
+    text.chars().try_fold(0u64, |seed, character| {
+        let value = BASE32_ALPHABET
+            .iter()
+            .position(|digit| *digit as char == character.to_ascii_uppercase())
+            .ok_or_else(|| format!("'{}' is not part of a shared seed", character))?;
+        seed.checked_mul(32)
+            .and_then(|seed| seed.checked_add(value as u64))
+            .ok_or_else(|| format!("{}{} is too large to be a seed", SHARE_PREFIX, text))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seeds_parse_in_every_format() {
+        assert_eq!(parse_seed("42"), Ok(42));
+        assert_eq!(parse_seed("  42 "), Ok(42));
+        assert_eq!(parse_seed("0x2a"), Ok(42));
+        assert_eq!(parse_seed("0X2A"), Ok(42));
+        assert_eq!(parse_seed("#1A"), Ok(42));
+        assert_eq!(parse_seed("#1a"), Ok(42));
+    }
+
+    #[test]
+    fn encoded_seeds_round_trip() {
+        assert_eq!(encode_seed(0), "#0");
+        assert_eq!(encode_seed(42), "#1A");
+        for seed in [0, 1, 31, 32, 42, 1 << 40, u64::MAX] {
+            assert_eq!(parse_seed(&encode_seed(seed)), Ok(seed));
+        }
+    }
+
+    #[test]
+    fn the_largest_seed_parses_in_every_format() {
+        assert_eq!(parse_seed("18446744073709551615"), Ok(u64::MAX));
+        assert_eq!(parse_seed("0xFFFFFFFFFFFFFFFF"), Ok(u64::MAX));
+        assert_eq!(parse_seed("18446744073709551616").ok(), None);
+        assert_eq!(parse_seed("0x10000000000000000").ok(), None);
+        let too_large = format!("{}0", encode_seed(u64::MAX));
+        assert_eq!(
+            parse_seed(&too_large),
+            Err(format!("{} is too large to be a seed", too_large))
+        );
+    }
+
+    #[test]
+    fn invalid_seeds_explain_themselves() {
+        assert_eq!(parse_seed(" "), Err("enter a seed".to_string()));
+        assert_eq!(
+            parse_seed("#"),
+            Err("enter the seed after the #".to_string())
+        );
+        assert_eq!(
+            parse_seed("#1U"),
+            Err("'U' is not part of a shared seed".to_string())
+        );
+        assert_eq!(
+            parse_seed("-1"),
+            Err(format!("\"-1\" is not a seed between 0 and {}", u64::MAX))
+        );
+        assert_eq!(
+            parse_seed("0xZ"),
+            Err(format!(
+                "\"0xZ\" is not a hex seed between 0x0 and 0x{:X}",
+                u64::MAX
+            ))
+        );
+    }
+}
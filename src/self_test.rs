@@ -0,0 +1,196 @@
+use std::io;
+use std::time::{Duration, Instant};
+
+use console_engine::{pixel, Color};
+
+use crate::board::Board;
+use crate::config::{MIN_PLAYABLE_HEIGHT, MIN_PLAYABLE_WIDTH};
+use crate::ConsoleContext;
+
+const TEST_DURATION: Duration = Duration::from_secs(3);
+// High enough that frame pacing never holds the test back.
+const UNTHROTTLED_FPS: u32 = 1000;
+// Sustaining three times the game's frame rate leaves room for slow frames.
+const COMFORTABLE_FPS_FACTOR: f64 = 3.0;
+const COLORS: [Color; 6] = [
+    Color::Red,
+    Color::Yellow,
+    Color::Green,
+    Color::Cyan,
+    Color::Blue,
+    Color::Magenta,
+];
+
+pub struct SelfTestResult {
+    pub width: u16,
+    pub height: u16,
+    pub frames: u32,
+    /// Everything written to the terminal over the test.
+    pub bytes: u64,
+    pub elapsed: Duration,
+}
+
+impl SelfTestResult {
+    pub fn fps(&self) -> f64 {
+        self.frames as f64 / self.elapsed.as_secs_f64().max(f64::EPSILON)
+    }
+
+    pub fn bytes_per_frame(&self) -> u64 {
+        self.bytes / u64::from(self.frames.max(1))
+    }
+}
+
+pub fn run(board: &Board) -> io::Result<SelfTestResult> {
+    let (width, height) = board.size();
+    let mut console = ConsoleContext::new(width, height, UNTHROTTLED_FPS)?;
+    let start = Instant::now();
+    let mut frames = 0;
+    while start.elapsed() < TEST_DURATION && !console.is_interrupt_pressed() {
+        console.wait_for_frame();
+        // Shift the pattern by one cell per frame so every cell changes.
+        let mut cell = frames as usize;
+        for y in 0..board.height() {
+            for x in 0..board.width() {
+                console.set_pixel(x, y, pixel::pxl_bg(' ', COLORS[cell % COLORS.len()]));
+                cell += 1;
+            }
+        }
+        console.draw();
+        frames += 1;
+    }
+    Ok(SelfTestResult {
+        width,
+        height,
+        frames,
+        bytes: console.bytes_written(),
+        elapsed: start.elapsed(),
+    })
+}
+
+/// The largest board, in the tested board's proportions, that redraws in
+/// full `target_fps` times a second at the measured speed, and never smaller
+/// than the smallest playable board.
+pub fn suggested_board(result: &SelfTestResult, target_fps: f64) -> (u16, u16) {
+    // Drawing time grows with the board's area, so each side shrinks by the
+    // square root of the speed that's missing.
+    let scale = (result.fps() / target_fps).min(1.0).sqrt();
+    let side = |length: u16, minimum: u16| ((f64::from(length) * scale) as u16).max(minimum);
+    (
+        side(result.width, MIN_PLAYABLE_WIDTH),
+        side(result.height, MIN_PLAYABLE_HEIGHT),
+    )
+}
+
+fn board_settings((width, height): (u16, u16)) -> String {
+    format!(
+        "[board] width = {}, height = {}, fit_to_terminal = false",
+        width, height
+    )
+}
+
+/// What to do about a terminal that sustained `result`, for a game drawing
+/// `frame_rate` frames per second.
+pub fn recommendation(result: &SelfTestResult, frame_rate: u32) -> String {
+    let fps = result.fps();
+    let comfortable_fps = f64::from(frame_rate) * COMFORTABLE_FPS_FACTOR;
+    if fps >= comfortable_fps {
+        "no changes needed, your terminal keeps up comfortably.".into()
+    } else if fps >= f64::from(frame_rate) {
+        format!(
+            "your terminal keeps up with the game's {} FPS with little headroom; \
+             if you see stutter, try a local terminal instead of SSH, or set \
+             {} in the config file.",
+            frame_rate,
+            board_settings(suggested_board(result, comfortable_fps))
+        )
+    } else {
+        format!(
+            "your terminal cannot keep up with the game's {} FPS; set {} in the \
+             config file, and try a local terminal instead of SSH.",
+            frame_rate,
+            board_settings(suggested_board(result, f64::from(frame_rate)))
+        )
+    }
+}
+
+pub fn report(result: &SelfTestResult, frame_rate: u32) {
+    println!(
+        "Your terminal sustained ~{:.0} FPS at {}x{} ({} frames in {:.1}s, {} bytes per frame).",
+        result.fps(),
+        result.width,
+        result.height,
+        result.frames,
+        result.elapsed.as_secs_f64(),
+        result.bytes_per_frame()
+    );
+    println!("Recommendation: {}", recommendation(result, frame_rate));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // An 80x20 board drawn at `fps` for a second.
+    fn measured(fps: u32) -> SelfTestResult {
+        SelfTestResult {
+            width: 80,
+            height: 20,
+            frames: fps,
+            bytes: u64::from(fps) * 20_000,
+            elapsed: Duration::from_secs(1),
+        }
+    }
+
+    fn verdict(fps: u32) -> String {
+        recommendation(&measured(fps), 60)
+    }
+
+    #[test]
+    fn the_comfortable_threshold_is_three_times_the_frame_rate() {
+        assert!(verdict(180).starts_with("no changes needed"));
+        assert!(verdict(179).contains("little headroom"));
+    }
+
+    #[test]
+    fn the_frame_rate_is_the_lowest_passing_rate() {
+        assert!(verdict(60).contains("little headroom"));
+        assert!(verdict(59).contains("cannot keep up"));
+        assert!(verdict(0).contains("cannot keep up"));
+    }
+
+    #[test]
+    fn recommendations_name_the_frame_rate() {
+        assert!(verdict(100).contains("the game's 60 FPS"));
+        assert!(verdict(30).contains("the game's 60 FPS"));
+    }
+
+    #[test]
+    fn slow_terminals_are_given_a_board_they_can_keep_up_with() {
+        assert_eq!(suggested_board(&measured(15), 60.0), (40, 10));
+        assert_eq!(suggested_board(&measured(60), 60.0), (80, 20));
+        assert_eq!(suggested_board(&measured(240), 60.0), (80, 20));
+        assert!(verdict(15).contains("[board] width = 40, height = 10, fit_to_terminal = false"));
+        assert!(verdict(90).contains("[board] width = 56, height = 14, fit_to_terminal = false"));
+    }
+
+    #[test]
+    fn boards_are_never_suggested_below_the_playable_minimum() {
+        assert_eq!(
+            suggested_board(&measured(1), 60.0),
+            (MIN_PLAYABLE_WIDTH, MIN_PLAYABLE_HEIGHT)
+        );
+    }
+
+    #[test]
+    fn fps_is_frames_over_elapsed_time() {
+        let result = SelfTestResult {
+            width: 80,
+            height: 20,
+            frames: 300,
+            bytes: 300 * 12_345,
+            elapsed: Duration::from_secs(3),
+        };
+        assert_eq!(result.fps(), 100.0);
+        assert_eq!(result.bytes_per_frame(), 12_345);
+    }
+}
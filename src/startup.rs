@@ -0,0 +1,292 @@
+use std::fmt;
+use std::io::{self, IsTerminal};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use crate::config::ConfigError;
+use crate::replay::ReplayError;
+
+#[derive(Debug)]
+pub enum StartupError {
+    NotATerminal,
+    UnknownTerminalSize,
+    TerminalTooSmall {
+        width: usize,
+        height: usize,
+        min_width: usize,
+        min_height: usize,
+    },
+    ConsoleInit(io::Error),
+    UnknownArgument(String),
+    MissingValue {
+        flag: &'static str,
+        expected: &'static str,
+    },
+    InvalidValue {
+        flag: &'static str,
+        value: String,
+        expected: &'static str,
+    },
+    ConflictingArguments {
+        first: &'static str,
+        second: &'static str,
+    },
+    InvalidConfig {
+        path: PathBuf,
+        error: ConfigError,
+    },
+    InvalidReplay {
+        path: PathBuf,
+        error: ReplayError,
+    },
+}
+
+impl StartupError {
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Self::NotATerminal => 10,
+            Self::UnknownTerminalSize => 11,
+            Self::TerminalTooSmall { .. } => 12,
+            Self::ConsoleInit(_) => 13,
+            // Every kind of bad command line shares a code.
+            Self::UnknownArgument(_)
+            | Self::MissingValue { .. }
+            | Self::InvalidValue { .. }
+            | Self::ConflictingArguments { .. } => 14,
+            Self::InvalidConfig { .. } => 15,
+            Self::InvalidReplay { .. } => 16,
+        }
+    }
+
+    pub fn suggestion(&self) -> String {
+        match self {
+            Self::NotATerminal => {
+                "run console_snek directly in a terminal, without piping its output".into()
+            }
+            Self::UnknownTerminalSize => {
+                "make sure $TERM is set and you are in an interactive terminal".into()
+            }
+            Self::TerminalTooSmall {
+                min_width,
+                min_height,
+                ..
+            } => format!(
+                "resize your terminal to at least {}x{} characters",
+                min_width, min_height
+            ),
+            Self::ConsoleInit(_) => "check that the terminal supports raw mode".into(),
+            Self::UnknownArgument(_) => "use --exit-code-mode <normal|outcome>, --self-test, \
+                                         --record <file>, or --replay <file>"
+                .into(),
+            Self::MissingValue { expected, .. } | Self::InvalidValue { expected, .. } => {
+                format!("use {}", expected)
+            }
+            Self::ConflictingArguments { first, second } => {
+                format!("pass either {} or {}, not both", first, second)
+            }
+            Self::InvalidConfig { path, .. } => format!(
+                "fix {}, or delete it to go back to the defaults",
+                path.display()
+            ),
+            Self::InvalidReplay { .. } => {
+                "check the path, or record the run again with this version of console_snek".into()
+            }
+        }
+    }
+}
+
+impl fmt::Display for StartupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotATerminal => write!(f, "standard output is not a terminal"),
+            Self::UnknownTerminalSize => write!(f, "could not determine the terminal size"),
+            Self::TerminalTooSmall {
+                width,
+                height,
+                min_width,
+                min_height,
+            } => write!(
+                f,
+                "your terminal is {}x{}; console_snek needs at least {}x{}",
+                width, height, min_width, min_height
+            ),
+            Self::ConsoleInit(error) => write!(f, "failed to initialize the console: {}", error),
+            Self::UnknownArgument(argument) => {
+                write!(f, "unrecognized argument \"{}\"", argument)
+            }
+            Self::MissingValue { flag, .. } => write!(f, "{} needs a value", flag),
+            Self::InvalidValue { flag, value, .. } => {
+                write!(f, "\"{}\" is not a valid value for {}", value, flag)
+            }
+            Self::ConflictingArguments { first, second } => {
+                write!(f, "{} can't be used with {}", first, second)
+            }
+            Self::InvalidConfig { path, error } => {
+                write!(
+                    f,
+                    "the config file {} is invalid: {}",
+                    path.display(),
+                    error
+                )
+            }
+            Self::InvalidReplay { path, error } => {
+                write!(f, "the replay {} is invalid: {}", path.display(), error)
+            }
+        }
+    }
+}
+
+pub fn check_terminal(min_width: usize, min_height: usize) -> Vec<StartupError> {
+    let mut errors = Vec::new();
+    if !io::stdout().is_terminal() {
+        errors.push(StartupError::NotATerminal);
+    }
+    match term_size::dimensions() {
+        None => errors.push(StartupError::UnknownTerminalSize),
+        Some((width, height)) if width < min_width || height < min_height => {
+            errors.push(StartupError::TerminalTooSmall {
+                width,
+                height,
+                min_width,
+                min_height,
+            })
+        }
+        Some(_) => (),
+    }
+    errors
+}
+
+pub fn present(errors: &[StartupError]) -> ExitCode {
+    eprintln!("console_snek could not start:");
+    for error in errors {
+        eprintln!();
+        eprintln!("  {}", error);
+        eprintln!("  Try: {}", error.suggestion());
+    }
+    ExitCode::from(errors.first().map_or(1, StartupError::exit_code))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::replay::ReplayError;
+
+    // Checks an error's message, suggestion, and exit code together.
+    fn assert_presents(error: StartupError, message: &str, suggestion: &str, exit_code: u8) {
+        assert_eq!(error.to_string(), message);
+        assert_eq!(error.suggestion(), suggestion);
+        assert_eq!(error.exit_code(), exit_code);
+    }
+
+    #[test]
+    fn terminal_errors() {
+        assert_presents(
+            StartupError::NotATerminal,
+            "standard output is not a terminal",
+            "run console_snek directly in a terminal, without piping its output",
+            10,
+        );
+        assert_presents(
+            StartupError::UnknownTerminalSize,
+            "could not determine the terminal size",
+            "make sure $TERM is set and you are in an interactive terminal",
+            11,
+        );
+        assert_presents(
+            StartupError::TerminalTooSmall {
+                width: 70,
+                height: 18,
+                min_width: 80,
+                min_height: 20,
+            },
+            "your terminal is 70x18; console_snek needs at least 80x20",
+            "resize your terminal to at least 80x20 characters",
+            12,
+        );
+        assert_presents(
+            StartupError::ConsoleInit(io::Error::other("no raw mode")),
+            "failed to initialize the console: no raw mode",
+            "check that the terminal supports raw mode",
+            13,
+        );
+    }
+
+    #[test]
+    fn argument_errors() {
+        assert_presents(
+            StartupError::UnknownArgument("--fast".to_string()),
+            "unrecognized argument \"--fast\"",
+            "use --exit-code-mode <normal|outcome>, --self-test, --record <file>, or --replay <file>",
+            14,
+        );
+        assert_presents(
+            StartupError::MissingValue {
+                flag: "--record",
+                expected: "--record <file>",
+            },
+            "--record needs a value",
+            "use --record <file>",
+            14,
+        );
+        assert_presents(
+            StartupError::InvalidValue {
+                flag: "--exit-code-mode",
+                value: "loud".to_string(),
+                expected: "--exit-code-mode normal or --exit-code-mode outcome",
+            },
+            "\"loud\" is not a valid value for --exit-code-mode",
+            "use --exit-code-mode normal or --exit-code-mode outcome",
+            14,
+        );
+        assert_presents(
+            StartupError::ConflictingArguments {
+                first: "--record",
+                second: "--replay",
+            },
+            "--record can't be used with --replay",
+            "pass either --record or --replay, not both",
+            14,
+        );
+    }
+
+    #[test]
+    fn file_errors() {
+        assert_presents(
+            StartupError::InvalidConfig {
+                path: PathBuf::from("config.toml"),
+                error: ConfigError::Syntax {
+                    line: 3,
+                    message: "expected a number".to_string(),
+                },
+            },
+            "the config file config.toml is invalid: line 3: expected a number",
+            "fix config.toml, or delete it to go back to the defaults",
+            15,
+        );
+        assert_presents(
+            StartupError::InvalidConfig {
+                path: PathBuf::from("config.toml"),
+                error: ConfigError::Unplayable(
+                    "a 19x10 board is too small to play on, boards must be at least 20x10"
+                        .to_string(),
+                ),
+            },
+            "the config file config.toml is invalid: a 19x10 board is too small to play on, boards must be at least 20x10",
+            "fix config.toml, or delete it to go back to the defaults",
+            15,
+        );
+        assert_presents(
+            StartupError::InvalidReplay {
+                path: PathBuf::from("run.replay"),
+                error: ReplayError::Invalid {
+                    line: 1,
+                    message: "this is not a console_snek replay".to_string(),
+                },
+            },
+            "the replay run.replay is invalid: line 1: this is not a console_snek replay",
+            "check the path, or record the run again with this version of console_snek",
+            16,
+        );
+    }
+}
@@ -0,0 +1,76 @@
+// Bump whenever the simulation rules or the hashed state layout change, so
+// hashes from different revisions never compare equal by accident.
+pub const RULES_REVISION: u32 = 5;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+// FNV-1a over explicitly little-endian values, so hashes are stable across
+// platforms and Rust versions, unlike `DefaultHasher`.
+pub struct StateHasher {
+    state: u64,
+}
+
+impl Default for StateHasher {
+    fn default() -> Self {
+        let mut hasher = Self {
+            state: FNV_OFFSET_BASIS,
+        };
+        hasher.write_u32(RULES_REVISION);
+        hasher
+    }
+}
+
+impl StateHasher {
+    pub fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.state ^= *byte as u64;
+            self.state = self.state.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    pub fn write_u8(&mut self, value: u8) {
+        self.write(&[value]);
+    }
+
+    pub fn write_u32(&mut self, value: u32) {
+        self.write(&value.to_le_bytes());
+    }
+
+    pub fn write_i32(&mut self, value: i32) {
+        self.write(&value.to_le_bytes());
+    }
+
+    pub fn write_u64(&mut self, value: u64) {
+        self.write(&value.to_le_bytes());
+    }
+
+    pub fn finish(&self) -> u64 {
+        self.state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_the_fnv_1a_reference() {
+        let mut hasher = StateHasher {
+            state: FNV_OFFSET_BASIS,
+        };
+        hasher.write(b"a");
+        assert_eq!(hasher.finish(), 0xaf63_dc4c_8601_ec8c);
+    }
+
+    #[test]
+    fn write_order_matters() {
+        let mut first = StateHasher::default();
+        first.write_u32(1);
+        first.write_u32(2);
+        let mut second = StateHasher::default();
+        second.write_u32(2);
+        second.write_u32(1);
+        assert_ne!(first.finish(), second.finish());
+    }
+}
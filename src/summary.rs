@@ -0,0 +1,130 @@
+use std::time::Duration;
+
+use crate::play_style::TurnStats;
+use crate::RunConfig;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EndCause {
+    SelfCollision,
+    WallCollision,
+    Quit,
+    BoardFull,
+}
+
+#[derive(Clone)]
+pub struct RunSummary {
+    pub config: RunConfig,
+    pub score: u32,
+    pub peak_score: u32,
+    pub length: usize,
+    pub ticks: u64,
+    pub tick_rate: u32,
+    pub end_cause: EndCause,
+    pub turns: TurnStats,
+}
+
+impl Default for RunSummary {
+    fn default() -> Self {
+        Self {
+            config: RunConfig::default(),
+            score: 0,
+            peak_score: 0,
+            length: 1,
+            ticks: 0,
+            tick_rate: crate::TICK_RATE,
+            end_cause: EndCause::Quit,
+            turns: TurnStats::default(),
+        }
+    }
+}
+
+impl RunSummary {
+    pub fn new(config: RunConfig) -> Self {
+        Self {
+            config,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_score(mut self, score: u32) -> Self {
+        self.score = score;
+        self
+    }
+
+    pub fn with_peak_score(mut self, peak_score: u32) -> Self {
+        self.peak_score = peak_score;
+        self
+    }
+
+    pub fn with_length(mut self, length: usize) -> Self {
+        self.length = length;
+        self
+    }
+
+    pub fn with_ticks(mut self, ticks: u64, tick_rate: u32) -> Self {
+        self.ticks = ticks;
+        self.tick_rate = tick_rate;
+        self
+    }
+
+    pub fn with_end_cause(mut self, end_cause: EndCause) -> Self {
+        self.end_cause = end_cause;
+        self
+    }
+
+    pub fn with_turn_stats(mut self, turns: TurnStats) -> Self {
+        self.turns = turns;
+        self
+    }
+
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs_f64(self.ticks as f64 / self.tick_rate.max(1) as f64)
+    }
+
+    pub fn headline(&self) -> &'static str {
+        match self.end_cause {
+            EndCause::BoardFull => "You filled the board!",
+            EndCause::SelfCollision | EndCause::WallCollision | EndCause::Quit => "Game Over.",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_describe_an_empty_run() {
+        let summary = RunSummary::default();
+        assert_eq!(summary.score, 0);
+        assert_eq!(summary.length, 1);
+        assert_eq!(summary.duration(), Duration::ZERO);
+        assert_eq!(summary.end_cause, EndCause::Quit);
+    }
+
+    #[test]
+    fn duration_is_ticks_over_tick_rate() {
+        let summary = RunSummary::default()
+            .with_score(12)
+            .with_peak_score(12)
+            .with_length(13)
+            .with_ticks(150, 60)
+            .with_end_cause(EndCause::SelfCollision);
+        assert_eq!(summary.duration(), Duration::from_millis(2_500));
+        assert_eq!(summary.length, 13);
+        assert!(summary.peak_score >= summary.score);
+        assert_eq!(summary.headline(), "Game Over.");
+    }
+
+    #[test]
+    fn a_zero_tick_rate_does_not_divide_by_zero() {
+        let summary = RunSummary::default().with_ticks(30, 0);
+        assert_eq!(summary.duration(), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn filling_the_board_has_its_own_headline() {
+        let summary = RunSummary::default().with_end_cause(EndCause::BoardFull);
+        assert_eq!(summary.headline(), "You filled the board!");
+    }
+}
@@ -0,0 +1,114 @@
+use std::time::Duration;
+
+use console_engine::crossterm::event::KeyEvent;
+use console_engine::events::Event;
+use console_engine::pixel::Pixel;
+use console_engine::{KeyCode, KeyModifiers};
+use console_snek::{Config, Renderer, SnekApp};
+
+const FRAME: Duration = Duration::from_micros(16_667);
+
+/// Keeps the characters of the last picture rendered into it.
+#[derive(Default)]
+struct Capture {
+    rows: Vec<Vec<char>>,
+}
+
+impl Renderer for Capture {
+    fn draw(&mut self, x: u16, y: u16, pixel: Pixel) {
+        let (x, y) = (usize::from(x), usize::from(y));
+        if self.rows.len() <= y {
+            self.rows.resize(y + 1, Vec::new());
+        }
+        let row = &mut self.rows[y];
+        if row.len() <= x {
+            row.resize(x + 1, ' ');
+        }
+        row[x] = pixel.chr;
+    }
+}
+
+fn screen(app: &SnekApp) -> String {
+    let mut capture = Capture::default();
+    app.render_into(&mut capture);
+    let rows: Vec<String> = capture
+        .rows
+        .iter()
+        .map(|row| row.iter().collect::<String>().trim_end().to_string())
+        .collect();
+    rows.join("\n")
+}
+
+fn press(app: &mut SnekApp, key: KeyCode) {
+    app.handle_event(Event::Key(KeyEvent::new(key, KeyModifiers::NONE)));
+    assert!(app.tick(FRAME), "a frame is played");
+}
+
+// Plays frames until `text` shows up, giving up after a minute of play.
+fn play_until(app: &mut SnekApp, text: &str) -> bool {
+    for _ in 0..60 * 60 {
+        if screen(app).contains(text) {
+            return true;
+        }
+        app.tick(FRAME);
+    }
+    false
+}
+
+#[test]
+fn a_game_plays_from_the_title_screen_to_quitting() {
+    let mut app = SnekApp::new(Config::default()).expect("the default settings are playable");
+    assert_eq!(app.size(), (80, 20));
+    app.tick(FRAME);
+    assert!(screen(&app).starts_with("Console Snek"));
+
+    // Walled mode, so the snake runs into the wall instead of wrapping.
+    press(&mut app, KeyCode::Right);
+    assert!(screen(&app).contains("Walled"));
+    press(&mut app, KeyCode::Enter);
+    assert!(screen(&app).starts_with("Score:"));
+    press(&mut app, KeyCode::Right);
+
+    // The run's score goes on the empty leaderboard a frame after it ends.
+    assert!(play_until(&mut app, "Game Over."));
+    app.tick(FRAME);
+    if screen(&app).contains("New high score") {
+        for key in [KeyCode::Char('a'), KeyCode::Char('b'), KeyCode::Enter] {
+            press(&mut app, key);
+        }
+        assert!(play_until(&mut app, "Game Over."));
+    }
+
+    press(&mut app, KeyCode::Char('n'));
+    assert!(app.is_finished());
+    assert!(!app.tick(FRAME));
+}
+
+#[test]
+fn time_short_of_a_frame_waits_for_the_next_tick() {
+    let mut app = SnekApp::new(Config::default()).expect("the default settings are playable");
+    assert!(!app.tick(FRAME / 2));
+    assert_eq!(screen(&app).trim(), "");
+    assert!(app.tick(FRAME / 2));
+    assert!(screen(&app).starts_with("Console Snek"));
+}
+
+#[test]
+fn a_stalled_host_does_not_race_through_the_frames_it_missed() {
+    let mut app = SnekApp::new(Config::default()).expect("the default settings are playable");
+    for key in [KeyCode::Right, KeyCode::Enter, KeyCode::Right] {
+        press(&mut app, key);
+    }
+    app.tick(Duration::from_secs(60));
+    assert!(
+        screen(&app).starts_with("Score:"),
+        "a minute's stall plays the run out"
+    );
+}
+
+#[test]
+fn quitting_from_the_title_screen_finishes_the_app() {
+    let mut app = SnekApp::new(Config::default()).expect("the default settings are playable");
+    press(&mut app, KeyCode::Char('q'));
+    assert!(app.is_finished());
+}